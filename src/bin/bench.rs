@@ -0,0 +1,134 @@
+//! Measures random playouts and move generation, the hot loop of MCTS, on 8x8 boards.
+//! Run with `cargo run --release --bin bench`
+//!
+//! The playouts are also played with the [`baseline`](baseline) generator, which allocates the moves
+//! of every pawn like `MoveGen` did before it generated them lazily, to show the gain
+
+use std::time::Instant;
+
+use board_game::board::{Board as BoardTrait, Outcome, Player};
+use chess::{BitBoard, Color};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use sf21_22::{
+    bitboard::BitBoardBackend, board::Board, move_gen::Move, pawn_board::PawnBoard, perft::perft,
+    sized_board::SizedBoard,
+};
+
+const PLAYOUTS: u32 = 200_000;
+const PERFT_DEPTH: u32 = 7;
+
+fn main() {
+    bench_baseline_playouts();
+    bench_playouts("Board", || Board::new(8));
+    bench_playouts("SizedBoard<8>", SizedBoard::<8>::new);
+    bench_perft("Board", &Board::new(8));
+    bench_perft("SizedBoard<8>", &SizedBoard::<8>::new());
+}
+
+/// Plays random games to the end, like the playouts of MCTS
+fn bench_playouts<B: BoardTrait>(name: &str, start: impl Fn() -> B) {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut plies = 0u64;
+
+    let time = Instant::now();
+    for _ in 0..PLAYOUTS {
+        let mut board = start();
+        while !board.is_done() {
+            board.play(board.random_available_move(&mut rng));
+            plies += 1;
+        }
+    }
+    let secs = time.elapsed().as_secs_f64();
+
+    println!(
+        "{name:<14} playouts: {:>10.0}/s, plies: {:>11.0}/s",
+        PLAYOUTS as f64 / secs,
+        plies as f64 / secs
+    );
+}
+
+/// Plays random games to the end with the [`baseline`](baseline) generator,
+/// picking moves like the default `random_available_move`: counting them, then generating them again
+fn bench_baseline_playouts() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut plies = 0u64;
+
+    let time = Instant::now();
+    for _ in 0..PLAYOUTS {
+        let mut board = Board::new(8);
+        while baseline::outcome(&board).is_none() {
+            let index = rng.gen_range(0..baseline::moves(&board).len());
+            board.play(baseline::moves(&board)[index]);
+            plies += 1;
+        }
+    }
+    let secs = time.elapsed().as_secs_f64();
+
+    println!(
+        "{:<14} playouts: {:>10.0}/s, plies: {:>11.0}/s",
+        "baseline",
+        PLAYOUTS as f64 / secs,
+        plies as f64 / secs
+    );
+}
+
+/// The move generation before it was lazy, for standard rules.
+/// Every position allocates the moves of each pawn, and the outcome generates all of them to count them
+mod baseline {
+    use super::*;
+
+    /// Returns the moves of the side to move, pawn by pawn
+    pub fn moves(board: &Board) -> Vec<Move> {
+        let color = board.side_to_move();
+        let playable = board.mask();
+        let mut moves = vec![];
+        for src in board.pieces_to_move().indices() {
+            let pawn = BitBoard::from_index(src);
+            let ahead = match color {
+                Color::White => pawn.shift_up(BitBoard::STRIDE),
+                Color::Black => pawn.shift_down(BitBoard::STRIDE),
+            };
+            let file = src % BitBoard::STRIDE;
+            let mut diagonals = BitBoard::EMPTY;
+            if file > 0 {
+                diagonals |= ahead.shift_down(1);
+            }
+            if file + 1 < board.width() {
+                diagonals |= ahead.shift_up(1);
+            }
+            let targets =
+                ((ahead & board.empty()) | (diagonals & board.pieces_not_to_move())) & playable;
+            moves.extend(
+                targets
+                    .indices()
+                    .map(|dest| Move::from_squares(BitBoard::square(src), BitBoard::square(dest))),
+            );
+        }
+        moves
+    }
+
+    /// Returns the outcome, counting every move to find out if the side to move is blocked
+    pub fn outcome(board: &Board) -> Option<Outcome> {
+        if !(board.pieces(Color::White) & board.win_rank(Color::White)).is_empty() {
+            Some(Outcome::WonBy(Player::A))
+        } else if !(board.pieces(Color::Black) & board.win_rank(Color::Black)).is_empty() {
+            Some(Outcome::WonBy(Player::B))
+        } else if moves(board).is_empty() {
+            Some(Outcome::Draw)
+        } else {
+            None
+        }
+    }
+}
+
+/// Counts the leaf nodes without transpositions, so every node generates its moves
+fn bench_perft<B: BoardTrait>(name: &str, board: &B) {
+    let time = Instant::now();
+    let nodes = perft(board, PERFT_DEPTH);
+    let secs = time.elapsed().as_secs_f64();
+
+    println!(
+        "{name:<14} perft {PERFT_DEPTH}: {nodes} nodes, {:>11.0} nodes/s",
+        nodes as f64 / secs
+    );
+}
@@ -0,0 +1,112 @@
+//! Plays the bots against each other on breakthrough, printing the results of each pairing
+//!
+//! ```text
+//! cargo run --release --bin breakthrough -- [<size>...] [--games <games per side>]
+//! ```
+//!
+//! Without sizes, every size from 4 to 8 is played. Each pairing plays every start from both sides
+
+use std::{env, process};
+
+use board_game::{
+    ai::{mcts::MCTSBot, minimax::MiniMaxBot, simple::RandomBot},
+    util::bot_game::run,
+};
+use rand::thread_rng;
+use sf21_22::{
+    bot::{
+        heuristic::{AdvancementHeuristic, AlwaysCaptureBot, AlwaysPushBot, MaterialHeuristic},
+        mcts_heuristic_bot::MCTSHeuristicBot,
+    },
+    breakthrough::Board,
+};
+
+const USAGE: &str = "usage: breakthrough [<size>...] [--games <games per side>]";
+
+/// The sizes breakthrough is played on, it needs at least 4 ranks for the two rows of pawns
+const SIZES: [usize; 5] = [4, 5, 6, 7, 8];
+/// The default amount of games per side of each pairing
+const GAMES_PER_SIDE: u32 = 50;
+
+const MIN_MAX_DEPTH: u32 = 4;
+const MCTS_ITERATIONS: u64 = 2_000;
+const MCTS_EXPLORATION: f32 = 2.0;
+
+/// Plays every bot against every bot, including itself
+macro_rules! round_robin {
+    ($size:expr, $games:expr, [$($bot:expr),* $(,)?]) => {
+        round_robin!(@rows $size, $games, [$($bot),*], [$($bot),*])
+    };
+    (@rows $size:expr, $games:expr, [$($bot_l:expr),*], $bots_r:tt) => {
+        $(round_robin!(@row $size, $games, $bot_l, $bots_r);)*
+    };
+    (@row $size:expr, $games:expr, $bot_l:expr, [$($bot_r:expr),*]) => {
+        $({
+            let result = run(|| Board::new($size), $bot_l, $bot_r, $games, true, |_, _| {});
+            println!(
+                "L: {} | W:{},D:{},L:{} | R: {}",
+                result.debug_l, result.wdl_l.win, result.wdl_l.draw, result.wdl_l.loss, result.debug_r
+            );
+        })*
+    };
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (sizes, games) = parse_args(&args).unwrap_or_else(|err| {
+        eprintln!("{err}\n{USAGE}");
+        process::exit(1);
+    });
+
+    for size in sizes {
+        println!("\nsize: {size}\n");
+        round_robin!(
+            size,
+            games,
+            [
+                || RandomBot::new(thread_rng()),
+                || AlwaysPushBot::new(thread_rng()),
+                || AlwaysCaptureBot::new(thread_rng()),
+                || MiniMaxBot::new(MIN_MAX_DEPTH, MaterialHeuristic, thread_rng()),
+                || MiniMaxBot::new(MIN_MAX_DEPTH, AdvancementHeuristic, thread_rng()),
+                || MCTSBot::new(MCTS_ITERATIONS, MCTS_EXPLORATION, thread_rng()),
+                || MCTSHeuristicBot::new(
+                    MCTS_ITERATIONS,
+                    MCTS_EXPLORATION,
+                    MaterialHeuristic,
+                    thread_rng()
+                ),
+            ]
+        );
+    }
+}
+
+/// Parses the arguments into the sizes and the games per side
+fn parse_args(args: &[String]) -> Result<(Vec<usize>, u32), String> {
+    let mut sizes = vec![];
+    let mut games = GAMES_PER_SIDE;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--games" => {
+                let value = args.next().ok_or("--games needs a value")?;
+                games = value
+                    .parse()
+                    .map_err(|_| format!("invalid amount of games '{value}'"))?;
+            }
+            size => {
+                let size: usize = size.parse().map_err(|_| format!("invalid size '{size}'"))?;
+                if !SIZES.contains(&size) {
+                    return Err(format!("invalid size {size}, must be 4 to 8"));
+                }
+                sizes.push(size);
+            }
+        }
+    }
+
+    if sizes.is_empty() {
+        sizes = SIZES.to_vec();
+    }
+    Ok((sizes, games))
+}
@@ -0,0 +1,91 @@
+//! Perft with divide output: the leaf nodes under each move, the total, and nodes per second
+//!
+//! ```text
+//! cargo run --release --bin perft -- <size> <depth> [--rules "<rules>"] [--start "<position>"]
+//! ```
+//!
+//! The rules are like `double-step en-passant`, and the start position is in board notation like `ppp/3/PPP w`
+
+use std::{env, process, time::Instant};
+
+use sf21_22::{
+    board::Board,
+    perft::{divide, perft},
+    rules::Rules,
+};
+
+const USAGE: &str = "usage: perft <size> <depth> [--rules \"<rules>\"] [--start \"<position>\"]";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (board, depth) = parse_args(&args).unwrap_or_else(|err| {
+        eprintln!("{err}\n{USAGE}");
+        process::exit(1);
+    });
+
+    println!("{board}rules: {}", board.rules());
+
+    let time = Instant::now();
+    let divide = divide(&board, depth);
+    let secs = time.elapsed().as_secs_f64();
+
+    for (mv, nodes) in &divide {
+        println!("{mv}: {nodes}");
+    }
+    // the divide doesn't cover depth 0
+    let nodes = if depth == 0 {
+        perft(&board, 0)
+    } else {
+        divide.iter().map(|(_, nodes)| nodes).sum()
+    };
+    println!();
+    println!("moves: {}", divide.len());
+    println!("nodes: {nodes}");
+    println!("time: {secs:.3}s");
+    println!("nps: {:.0}", nodes as f64 / secs);
+}
+
+/// Parses the arguments into the start position and depth
+fn parse_args(args: &[String]) -> Result<(Board, u32), String> {
+    let mut positional = vec![];
+    let mut rules = Rules::STANDARD;
+    let mut start = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rules" => {
+                let value = args.next().ok_or("--rules needs a value")?;
+                rules = value.parse().map_err(|err| format!("{err}"))?;
+            }
+            "--start" => {
+                let value = args.next().ok_or("--start needs a value")?;
+                let board: Board = value.parse().map_err(|err| format!("{err}"))?;
+                start = Some(board);
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    let [size, depth] = positional[..] else {
+        return Err("expected a size and a depth".to_string());
+    };
+    let size: usize = size.parse().map_err(|_| format!("invalid size '{size}'"))?;
+    let depth: u32 = depth
+        .parse()
+        .map_err(|_| format!("invalid depth '{depth}'"))?;
+
+    let board = match start {
+        Some(board) if (board.width(), board.height()) != (size, size) => {
+            return Err(format!(
+                "the start position is {}x{}, not {size}x{size}",
+                board.width(),
+                board.height()
+            ))
+        }
+        Some(board) => board,
+        None if (3..=8).contains(&size) => Board::new(size),
+        None => return Err(format!("invalid size {size}, must be 3 to 8")),
+    };
+    Ok((board.with_rules(rules), depth))
+}
@@ -24,7 +24,29 @@ fn main() {
         "MCTSAdvancement",
         "MCTSMaterial",
     ];
-    for (bot_l, name) in bots.into_iter().zip(bot_names.into_iter()) {
+    print_matrix(&bots, &bot_names);
+
+    // the clocked tournament only pairs bots that search under the same clock
+    let clocked_bots = [
+        "MCTSHeuristicBot::new(MCTS_CLOCKED_ITERATIONS, MCTS_EXPLORATION, SolverHeuristicSimplified, thread_rng()).with_time_control(TIME_CONTROL)",
+        "MCTSHeuristicBot::new(MCTS_CLOCKED_ITERATIONS, MCTS_EXPLORATION, MaterialHeuristic, thread_rng()).with_time_control(TIME_CONTROL)",
+        "MCTSHeuristicBot::new(MCTS_CLOCKED_ITERATIONS, MCTS_EXPLORATION, AdvancementHeuristic, thread_rng()).with_time_control(TIME_CONTROL)",
+        "AlphaBetaBot::new(ALPHA_BETA_MAX_DEPTH, MaterialHeuristic).with_time_control(TIME_CONTROL)",
+        "TablebaseBot::new(tablebase, AlphaBetaBot::new(ALPHA_BETA_MAX_DEPTH, MaterialHeuristic).with_time_control(TIME_CONTROL), thread_rng())",
+    ];
+    let clocked_bot_names = [
+        "MCTSSolver",
+        "MCTSMaterial",
+        "MCTSAdvancement",
+        "AlphaBeta",
+        "Tablebase",
+    ];
+    print_matrix(&clocked_bots, &clocked_bot_names);
+}
+
+/// Prints the games of every bot against every bot
+fn print_matrix(bots: &[&str], bot_names: &[&str]) {
+    for (bot_l, name) in bots.iter().zip(bot_names) {
         println!(
             r#"println!("Running (at {{}}): {}", OffsetDateTime::now_utc());"#,
             name
@@ -1,5 +1,6 @@
 use std::{
     collections::HashMap,
+    env,
     fs::File,
     io::{self, BufRead, BufReader, ErrorKind, Result},
     ops::{Add, Neg},
@@ -9,39 +10,53 @@ use std::{
 use once_cell::sync::Lazy;
 use regex::{Captures, Regex};
 use serde::{Deserialize, Serialize};
-use sf21_22::{output_path, SIZES};
+use sf21_22::{clocked_output_path, output_path, SIZES};
 
 use self::BotType::*;
 
+/// The bots of the tournament
+const BOT_TYPES: [BotType; 11] = [
+    Random,
+    AlwaysPush,
+    AlwaysCapture,
+    MiniMax,
+    MiniMaxAdvance,
+    MiniMaxCapture,
+    MCTS,
+    MCTSSolver,
+    MCTSAdvance,
+    MCTSCapture,
+    Tablebase,
+];
+/// The bots of the clocked tournament
+const CLOCKED_BOT_TYPES: [BotType; 5] =
+    [MCTSSolver, MCTSAdvance, MCTSCapture, AlphaBeta, Tablebase];
+
+/// Prints the results of the tournament, or of the clocked one with `--clocked`
 fn main() {
-    let file = File::open(output_path()).unwrap();
+    let clocked = env::args().skip(1).any(|arg| arg == "--clocked");
+    let (path, bot_types) = if clocked {
+        (clocked_output_path(), &CLOCKED_BOT_TYPES[..])
+    } else {
+        (output_path(), &BOT_TYPES[..])
+    };
+    let file = File::open(path).unwrap();
     let read = BufReader::new(&file);
 
     let results = parse_results(read).unwrap();
 
-    graph_data(&results);
+    graph_data(&results, bot_types);
 }
 
-pub fn graph_data(results: &Results) {
-    let bot_types = [
-        Random,
-        AlwaysPush,
-        AlwaysCapture,
-        MiniMax,
-        MiniMaxAdvance,
-        MiniMaxCapture,
-        MCTS,
-        MCTSSolver,
-        MCTSAdvance,
-        MCTSCapture,
-    ];
-
+pub fn graph_data(results: &Results, bot_types: &[BotType]) {
+    println!("rules: {}", results.rules);
+    println!("time control: {}", results.time_control);
     print!("{:<14}: ", "size");
     for size in SIZES {
         print!("{:^9}|", size);
     }
     println!();
-    for bot_type in bot_types {
+    for &bot_type in bot_types {
         print!("{:<14}: ", format!("{:?}", bot_type));
         for size in SIZES {
             print!(
@@ -68,6 +83,8 @@ pub enum BotType {
     MCTSSolver,
     MCTSAdvance,
     MCTSCapture,
+    AlphaBeta,
+    Tablebase,
 }
 
 impl FromStr for BotType {
@@ -85,6 +102,12 @@ impl FromStr for BotType {
 "MCTSHeuristicBot { iterations: 10000, exploration_weight: 2, heuristic: SolverHeuristicSimplified }" => MCTSSolver,
 "MCTSHeuristicBot { iterations: 10000, exploration_weight: 2, heuristic: MaterialHeuristic }" => MCTSCapture,
 "MCTSHeuristicBot { iterations: 10000, exploration_weight: 2, heuristic: AdvancementHeuristic }" => MCTSAdvance,
+"MCTSHeuristicBot { iterations: unlimited, exploration_weight: 2, heuristic: SolverHeuristicSimplified }" => MCTSSolver,
+"MCTSHeuristicBot { iterations: unlimited, exploration_weight: 2, heuristic: MaterialHeuristic }" => MCTSCapture,
+"MCTSHeuristicBot { iterations: unlimited, exploration_weight: 2, heuristic: AdvancementHeuristic }" => MCTSAdvance,
+"AlphaBetaBot { depth: 64, node_limit: None, heuristic: MaterialHeuristic }" => AlphaBeta,
+"TablebaseBot { fallback: MiniMaxBot { depth: 10, heuristic: SolverHeuristicSimplified } }" => Tablebase,
+"TablebaseBot { fallback: AlphaBetaBot { depth: 64, node_limit: None, heuristic: MaterialHeuristic } }" => Tablebase,
 _ => return Err(io::Error::new(ErrorKind::InvalidData, format!("Expected bot Debug value, got {}", s)))
         })
     }
@@ -92,6 +115,10 @@ _ => return Err(io::Error::new(ErrorKind::InvalidData, format!("Expected bot Deb
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Results {
+    /// The rules the games were played with, like `standard` or `stalemate-loss`
+    pub rules: String,
+    /// The clock every bot played with, or `none`
+    pub time_control: String,
     inner: HashMap<u32, HashMap<ResultKey, WDL>>,
 }
 
@@ -163,6 +190,10 @@ pub fn parse_results(read: impl BufRead) -> Result<Results> {
         map
     };
     let mut selected_map = map.get_mut(&3).unwrap();
+    // results from before the rules were written were played with standard rules
+    let mut rules = String::from("standard");
+    // and without a clock
+    let mut time_control = String::from("none");
 
     for line in read.lines() {
         let line = line?;
@@ -173,7 +204,11 @@ pub fn parse_results(read: impl BufRead) -> Result<Results> {
 
         static RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"size: (?P<size>[3-8])"#).unwrap());
 
-        if let Some(captures) = RE.captures(&line) {
+        if let Some(r) = line.strip_prefix("rules: ") {
+            rules = r.to_string();
+        } else if let Some(t) = line.strip_prefix("time control: ") {
+            time_control = t.to_string();
+        } else if let Some(captures) = RE.captures(&line) {
             let size = capture_u32(&captures, "size").unwrap();
             selected_map = map.get_mut(&size).unwrap();
         } else {
@@ -182,7 +217,11 @@ pub fn parse_results(read: impl BufRead) -> Result<Results> {
         }
     }
 
-    Ok(Results { inner: map })
+    Ok(Results {
+        rules,
+        time_control,
+        inner: map,
+    })
 }
 
 #[derive(Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
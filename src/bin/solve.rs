@@ -0,0 +1,95 @@
+//! Solves hexapawn from the start position for each size, printing the value with perfect play
+//!
+//! ```text
+//! cargo run --release --bin solve -- [<size>...] [--rules "<rules>"] [--nodes <limit>]
+//! ```
+//!
+//! Without sizes, every size in [`SIZES`](sf21_22::SIZES) is tried. A size that takes more than
+//! the node limit is reported as unsolved
+
+use std::{env, process, time::Instant};
+
+use board_game::wdl::OutcomeWDL;
+use sf21_22::{board::Board, rules::Rules, solver::Solver, SIZES};
+
+const USAGE: &str = "usage: solve [<size>...] [--rules \"<rules>\"] [--nodes <limit>]";
+
+/// The default node limit for each size
+const NODE_LIMIT: u64 = 20_000_000;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (sizes, rules, node_limit) = parse_args(&args).unwrap_or_else(|err| {
+        eprintln!("{err}\n{USAGE}");
+        process::exit(1);
+    });
+
+    println!("rules: {rules}");
+    for size in sizes {
+        let board = Board::new(size).with_rules(rules);
+        let mut solver = Solver::with_node_limit(node_limit);
+
+        let time = Instant::now();
+        let solution = solver.solve(&board);
+        let secs = time.elapsed().as_secs_f64();
+
+        let value = match &solution {
+            Some(solution) => {
+                // white moves first
+                let value = match solution.outcome {
+                    OutcomeWDL::Win => "white wins",
+                    OutcomeWDL::Draw => "draw",
+                    OutcomeWDL::Loss => "black wins",
+                };
+                let best_move = solution
+                    .best_move()
+                    .map_or("none".to_string(), |mv| mv.to_string());
+                format!(
+                    "{value} in {} plies, best move {best_move}",
+                    solution.distance
+                )
+            }
+            None => format!("unsolved after {node_limit} nodes"),
+        };
+        println!(
+            "{size}x{size}: {value} ({} nodes, {} positions, {secs:.3}s)",
+            solver.nodes(),
+            solver.table_size()
+        );
+    }
+}
+
+/// Parses the arguments into the sizes, rules and node limit
+fn parse_args(args: &[String]) -> Result<(Vec<usize>, Rules, u64), String> {
+    let mut sizes = vec![];
+    let mut rules = Rules::STANDARD;
+    let mut node_limit = NODE_LIMIT;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rules" => {
+                let value = args.next().ok_or("--rules needs a value")?;
+                rules = value.parse().map_err(|err| format!("{err}"))?;
+            }
+            "--nodes" => {
+                let value = args.next().ok_or("--nodes needs a value")?;
+                node_limit = value
+                    .parse()
+                    .map_err(|_| format!("invalid node limit '{value}'"))?;
+            }
+            size => {
+                let size: usize = size.parse().map_err(|_| format!("invalid size '{size}'"))?;
+                if !SIZES.contains(&size) {
+                    return Err(format!("invalid size {size}, must be 3 to 8"));
+                }
+                sizes.push(size);
+            }
+        }
+    }
+
+    if sizes.is_empty() {
+        sizes = SIZES.to_vec();
+    }
+    Ok((sizes, rules, node_limit))
+}
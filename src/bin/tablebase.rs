@@ -0,0 +1,85 @@
+//! Generates tablebases by retrograde analysis and saves them to
+//! [`tablebases_path`](sf21_22::tablebases_path).
+//! They have the positions reachable from the starting position, or every legal position with `--all`,
+//! which only 3x3 is small enough for
+//!
+//! ```text
+//! cargo run --release --bin tablebase -- <size>... [--rules "<rules>"] [--all]
+//! ```
+
+use std::{env, fs, process, time::Instant};
+
+use chess::BitBoard;
+
+use sf21_22::{board::Board, rules::Rules, tablebase::Tablebase, tablebases_path, SIZES};
+
+const USAGE: &str = "usage: tablebase <size>... [--rules \"<rules>\"] [--all]";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (sizes, rules, all) = parse_args(&args).unwrap_or_else(|err| {
+        eprintln!("{err}\n{USAGE}");
+        process::exit(1);
+    });
+
+    fs::create_dir_all(tablebases_path()).unwrap();
+    println!("rules: {rules}");
+    for size in sizes {
+        let board = Board::new(size).with_rules(rules);
+
+        let time = Instant::now();
+        let tablebase = if all {
+            Tablebase::generate_all::<BitBoard>(size, size, rules)
+        } else {
+            Tablebase::generate_reachable(&board)
+        };
+        let tablebase = match tablebase {
+            Ok(tablebase) => tablebase,
+            Err(err) => {
+                println!("{size}x{size}: {err}");
+                continue;
+            }
+        };
+        let secs = time.elapsed().as_secs_f64();
+
+        let path = Tablebase::path(size, size, rules);
+        tablebase.save(&path).unwrap();
+        println!(
+            "{size}x{size}: {} for white, {} positions, {} bytes, {secs:.3}s, saved to {}",
+            tablebase.probe(&board).unwrap(),
+            tablebase.len(),
+            fs::metadata(&path).unwrap().len(),
+            path.display()
+        );
+    }
+}
+
+/// Parses the arguments into the sizes, rules and whether to generate every legal position
+fn parse_args(args: &[String]) -> Result<(Vec<usize>, Rules, bool), String> {
+    let mut sizes = vec![];
+    let mut rules = Rules::STANDARD;
+    let mut all = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rules" => {
+                let value = args.next().ok_or("--rules needs a value")?;
+                rules = value.parse().map_err(|err| format!("{err}"))?;
+            }
+            "--all" => all = true,
+            size => {
+                let size: usize = size.parse().map_err(|_| format!("invalid size '{size}'"))?;
+                if !SIZES.contains(&size) {
+                    return Err(format!("invalid size {size}, must be 3 to 8"));
+                }
+                sizes.push(size);
+            }
+        }
+    }
+
+    if sizes.is_empty() {
+        return Err("expected at least one size".to_string());
+    }
+    Ok((sizes, rules, all))
+}
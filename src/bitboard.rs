@@ -0,0 +1,313 @@
+//! Bitboard backends for [`Board`](crate::board::Board)
+//!
+//! [`chess`](chess)'s 64 bit [`BitBoard`](BitBoard) is the default, and fits boards up to 8x8.
+//! [`BitBoard128`](BitBoard128) fits boards up to 11x11.
+//!
+//! Square `index` is at file `index % STRIDE` and rank `index / STRIDE`
+
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+use std::panic::{RefUnwindSafe, UnwindSafe};
+
+use chess::{BitBoard, Square, ALL_SQUARES};
+
+use crate::consts::{board_mask, EMPTY_BB, FILES, RANKS};
+
+/// A bitboard that a [`Board`](crate::board::Board) can store its pawns in
+pub trait BitBoardBackend:
+    'static
+    + Copy
+    + Eq
+    + PartialOrd
+    + Hash
+    + Debug
+    + Default
+    + Send
+    + Sync
+    + UnwindSafe
+    + RefUnwindSafe
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+    + BitAndAssign
+    + BitOrAssign
+    + BitXorAssign
+{
+    /// The square type used in moves
+    type Square: 'static
+        + Copy
+        + Eq
+        + Ord
+        + Hash
+        + Debug
+        + Display
+        + Default
+        + Send
+        + Sync
+        + UnwindSafe
+        + RefUnwindSafe;
+
+    /// The amount of bits in each rank
+    const STRIDE: usize;
+    /// The largest width and height of a board
+    const MAX_SIZE: usize;
+    /// An empty bitboard
+    const EMPTY: Self;
+
+    /// Returns a bitboard with only the square at `index`
+    fn from_index(index: usize) -> Self;
+
+    /// Returns the square at `index`
+    fn square(index: usize) -> Self::Square;
+
+    /// Returns the index of a square
+    fn index(sq: Self::Square) -> usize;
+
+    /// Returns the index of the least significant one bit. The bitboard can't be empty
+    fn lsb(self) -> usize;
+
+    /// Returns the amount of one bits
+    fn pop_count(self) -> u32;
+
+    /// Shifts every bit up by `n`, towards the top of the board
+    fn shift_up(self, n: usize) -> Self;
+
+    /// Shifts every bit down by `n`, towards the bottom of the board
+    fn shift_down(self, n: usize) -> Self;
+
+    /// Returns a whole rank, from rank 1 \[0\]
+    fn rank(rank: usize) -> Self;
+
+    /// Returns a whole file, from file A \[0\]
+    fn file(file: usize) -> Self;
+
+    /// Returns the playing area for a board with a width and height
+    fn board_mask(width: usize, height: usize) -> Self;
+
+    /// Mirrors the bitboard across the vertical axis of a board with `width` files.
+    /// Every bit must be inside of the first `width` files
+    fn mirror(self, width: usize) -> Self {
+        let mut mirrored = Self::EMPTY;
+        for index in self.indices() {
+            let (file, rank) = (index % Self::STRIDE, index / Self::STRIDE);
+            mirrored |= Self::from_index(rank * Self::STRIDE + width - 1 - file);
+        }
+        mirrored
+    }
+
+    /// Returns whether there are no one bits
+    fn is_empty(self) -> bool {
+        self == Self::EMPTY
+    }
+
+    /// Returns an iterator over the indices of the one bits, from least significant
+    fn indices(self) -> Indices<Self> {
+        Indices(self)
+    }
+}
+
+/// An iterator over the indices of the one bits of a bitboard
+#[derive(Debug, Clone)]
+pub struct Indices<BB: BitBoardBackend>(BB);
+
+impl<BB: BitBoardBackend> Iterator for Indices<BB> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0.is_empty() {
+            return None;
+        }
+
+        let index = self.0.lsb();
+        self.0 ^= BB::from_index(index);
+        Some(index)
+    }
+}
+
+/// Mirrors a bitboard horizontally, so file A becomes file H.
+/// See [flipping and mirroring](https://www.chessprogramming.org/Flipping_Mirroring_and_Rotating#MirrorHorizontally)
+fn mirror_horizontal(mut bb: u64) -> u64 {
+    const K1: u64 = 0x5555555555555555;
+    const K2: u64 = 0x3333333333333333;
+    const K4: u64 = 0x0F0F0F0F0F0F0F0F;
+    bb = ((bb >> 1) & K1) | ((bb & K1) << 1);
+    bb = ((bb >> 2) & K2) | ((bb & K2) << 2);
+    bb = ((bb >> 4) & K4) | ((bb & K4) << 4);
+    bb
+}
+
+impl BitBoardBackend for BitBoard {
+    type Square = Square;
+
+    const STRIDE: usize = 8;
+    const MAX_SIZE: usize = 8;
+    const EMPTY: Self = EMPTY_BB;
+
+    fn from_index(index: usize) -> Self {
+        BitBoard(1 << index)
+    }
+
+    fn square(index: usize) -> Self::Square {
+        ALL_SQUARES[index]
+    }
+
+    fn index(sq: Self::Square) -> usize {
+        sq.to_index()
+    }
+
+    fn lsb(self) -> usize {
+        self.0.trailing_zeros() as usize
+    }
+
+    fn pop_count(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    fn shift_up(self, n: usize) -> Self {
+        BitBoard(self.0 << n)
+    }
+
+    fn shift_down(self, n: usize) -> Self {
+        BitBoard(self.0 >> n)
+    }
+
+    fn rank(rank: usize) -> Self {
+        BitBoard(RANKS[rank])
+    }
+
+    fn file(file: usize) -> Self {
+        BitBoard(FILES[file])
+    }
+
+    fn board_mask(width: usize, height: usize) -> Self {
+        BitBoard(board_mask(width, height))
+    }
+
+    fn mirror(self, width: usize) -> Self {
+        // mirroring the whole 8x8 board puts the pawns on the last `width` files, so shift them back
+        BitBoard(mirror_horizontal(self.0) >> (8 - width))
+    }
+}
+
+/// A 128 bit bitboard for boards up to 11x11, with 11 bits in each rank
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Default, Hash)]
+pub struct BitBoard128(pub u128);
+
+/// A square of a [`BitBoard128`](BitBoard128), from a1 to k11
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Default, Hash)]
+pub struct Square128(u8);
+
+impl Square128 {
+    /// Creates a new `Square128` from a file and rank, from 0 to 10
+    pub fn new(file: usize, rank: usize) -> Self {
+        assert!(file < 11 && rank < 11, "Invalid square, must be a1 to k11");
+        Square128((rank * BitBoard128::STRIDE + file) as u8)
+    }
+
+    /// Returns the file, from 0 to 10
+    pub fn file(&self) -> usize {
+        self.0 as usize % BitBoard128::STRIDE
+    }
+
+    /// Returns the rank, from 0 to 10
+    pub fn rank(&self) -> usize {
+        self.0 as usize / BitBoard128::STRIDE
+    }
+}
+
+impl Display for Square128 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            (b'a' + self.file() as u8) as char,
+            self.rank() + 1
+        )
+    }
+}
+
+/// Implements a binary operator and its assignment operator for `BitBoard128`
+macro_rules! bitboard128_op {
+    ($op:ident, $fn:ident, $op_assign:ident, $fn_assign:ident) => {
+        impl $op for BitBoard128 {
+            type Output = BitBoard128;
+
+            fn $fn(self, rhs: Self) -> Self::Output {
+                BitBoard128(self.0.$fn(rhs.0))
+            }
+        }
+
+        impl $op_assign for BitBoard128 {
+            fn $fn_assign(&mut self, rhs: Self) {
+                self.0.$fn_assign(rhs.0)
+            }
+        }
+    };
+}
+
+bitboard128_op!(BitAnd, bitand, BitAndAssign, bitand_assign);
+bitboard128_op!(BitOr, bitor, BitOrAssign, bitor_assign);
+bitboard128_op!(BitXor, bitxor, BitXorAssign, bitxor_assign);
+
+impl Not for BitBoard128 {
+    type Output = BitBoard128;
+
+    fn not(self) -> Self::Output {
+        BitBoard128(!self.0)
+    }
+}
+
+impl BitBoardBackend for BitBoard128 {
+    type Square = Square128;
+
+    const STRIDE: usize = 11;
+    const MAX_SIZE: usize = 11;
+    const EMPTY: Self = BitBoard128(0);
+
+    fn from_index(index: usize) -> Self {
+        BitBoard128(1 << index)
+    }
+
+    fn square(index: usize) -> Self::Square {
+        Square128(index as u8)
+    }
+
+    fn index(sq: Self::Square) -> usize {
+        sq.0 as usize
+    }
+
+    fn lsb(self) -> usize {
+        self.0.trailing_zeros() as usize
+    }
+
+    fn pop_count(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    fn shift_up(self, n: usize) -> Self {
+        BitBoard128(self.0 << n)
+    }
+
+    fn shift_down(self, n: usize) -> Self {
+        BitBoard128(self.0 >> n)
+    }
+
+    fn rank(rank: usize) -> Self {
+        BitBoard128(((1 << Self::STRIDE) - 1) << (rank * Self::STRIDE))
+    }
+
+    fn file(file: usize) -> Self {
+        Self::board_mask(1, Self::MAX_SIZE).shift_up(file)
+    }
+
+    fn board_mask(width: usize, height: usize) -> Self {
+        let rank = (1 << width) - 1;
+        let mut mask = 0;
+        for i in 0..height {
+            mask |= rank << (i * Self::STRIDE);
+        }
+        BitBoard128(mask)
+    }
+}
@@ -1,124 +1,356 @@
 //! Board representation of hexapawn
 
+use std::cmp::Ordering;
+use std::error::Error;
 use std::fmt::Display;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::marker::PhantomData;
 use std::ops::ControlFlow;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
 
 use board_game::board::Board as BoardTrait;
 use board_game::board::BoardMoves;
+use board_game::board::BoardSymmetry;
 use board_game::board::Outcome;
 use board_game::board::Player;
-use board_game::board::UnitSymmetryBoard;
+use board_game::symmetry::D1Symmetry;
 use chess::{BitBoard, Color};
 use internal_iterator::Internal;
 use internal_iterator::InternalIterator;
 use internal_iterator::IteratorExt;
 
-use crate::consts::EMPTY_BB;
-use crate::consts::{RANKS, START_POS_BLACK, START_POS_WHITE};
+use crate::bitboard::BitBoardBackend;
+use crate::consts::{ZOBRIST_BLACK, ZOBRIST_BLACK_TO_MOVE, ZOBRIST_EN_PASSANT, ZOBRIST_WHITE};
+use crate::move_gen::Mask;
 use crate::move_gen::Move;
+use crate::move_gen::MoveError;
 use crate::move_gen::MoveGen;
-
-/// A representation of hexapawn of various sizes
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct Board {
+use crate::pawn_board::{fmt_pawns, PawnBoard};
+use crate::rules::{Rules, StalemateRule};
+
+/// Whether a board has been given rules under which the player who moves can lose by that move.
+/// [`can_lose_after_move`](BoardTrait::can_lose_after_move) can't see the rules of a board,
+/// so it answers for every board created so far
+static LOSING_RULES: AtomicBool = AtomicBool::new(false);
+
+/// A representation of hexapawn of various sizes, square or rectangular.
+///
+/// The pawns are stored in a [`BitBoardBackend`](BitBoardBackend), by default the 64 bit [`BitBoard`](BitBoard)
+/// for boards up to 8x8. [`BitBoard128`](crate::bitboard::BitBoard128) fits boards up to 11x11
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Board<BB: BitBoardBackend = BitBoard> {
     /// White's pawns
-    white: BitBoard, // stored as a unsigned 64 bit integer bitboard
+    white: BB,
     /// Black's pawns
-    black: BitBoard,
+    black: BB,
     /// The side to move
     side_to_move: Color,
-    /// The size of the board, from 3 to 8
-    size: usize,
+    /// The width of the board, from 3 to `BB::MAX_SIZE`
+    width: usize,
+    /// The height of the board, from 3 to `BB::MAX_SIZE`
+    height: usize,
+    /// The rule variants
+    rules: Rules,
+    /// The index of the square skipped by a double step on the last move, which can be captured en passant
+    en_passant: Option<usize>,
+    /// The Zobrist key, updated incrementally
+    zobrist: u64,
+}
+
+impl<BB: BitBoardBackend> Hash for Board<BB> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // the Zobrist key already depends on the whole position
+        state.write_u64(self.zobrist);
+    }
 }
 
-impl Display for Board {
+impl<BB: BitBoardBackend> Display for Board<BB> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut white = self.white.0;
-        let mut black = self.black.0;
-
-        // get 8th rank, shift up
-        for i in 0..8 {
-            // shrink into u8
-            let mut w_rank = ((white & RANKS[7]) >> 56) as u8;
-            let mut b_rank = ((black & RANKS[7]) >> 56) as u8;
-
-            for _ in 0..8 {
-                // get first
-                let w_sq = w_rank & 1;
-                let b_sq = b_rank & 1;
-
-                match (w_sq, b_sq) {
-                    (0, 0) => f.write_str(" ")?,
-                    (1, 0) => f.write_str("♙")?,    // white pawn
-                    (0, 1) => f.write_str("♟︎")?, // black pawn
-                    (1, 1) => panic!("Board has two pawns in the same place"),
-                    _ => {}
-                }
+        fmt_pawns(f, self.white, self.black, self.width, self.height)
+    }
+}
+
+impl Board {
+    /// Creates a new square `Board`
+    /// The size must be 3 to 8
+    pub fn new(size: usize) -> Self {
+        Board::with_dimensions(size, size)
+    }
 
-                w_rank >>= 1;
-                b_rank >>= 1;
+    /// Creates a new `Board` with a width and height.
+    /// The width and height must be 3 to 8
+    pub fn with_dimensions(width: usize, height: usize) -> Self {
+        Board::starting_position(width, height)
+    }
+}
+
+impl<BB: BitBoardBackend> Board<BB> {
+    /// Creates a new `Board` with a width and height on any bitboard backend,
+    /// like `Board::<BitBoard128>::starting_position(11, 11)`.
+    /// The width and height must be 3 to `BB::MAX_SIZE`
+    pub fn starting_position(width: usize, height: usize) -> Self {
+        assert!(
+            (3..=BB::MAX_SIZE).contains(&width),
+            "Invalid width, must be 3 to {}",
+            BB::MAX_SIZE
+        );
+        assert!(
+            (3..=BB::MAX_SIZE).contains(&height),
+            "Invalid height, must be 3 to {}",
+            BB::MAX_SIZE
+        );
+        // white starts on rank 1
+        let start = BB::board_mask(width, 1);
+        let mut board = Board {
+            white: start,
+            // black starts on the top rank
+            black: start.shift_up(BB::STRIDE * (height - 1)),
+            side_to_move: Color::White,
+            width,
+            height,
+            rules: Rules::STANDARD,
+            en_passant: None,
+            zobrist: 0,
+        };
+        board.zobrist = board.compute_zobrist();
+        board
+    }
+
+    /// Creates a square `Board` from a custom position
+    ///
+    /// Fails if the size isn't 3 to `BB::MAX_SIZE`, if pawns overlap, if pawns are outside the board
+    /// or if the game was already won before the side to move got to move
+    pub fn from_parts(
+        size: usize,
+        white: BB,
+        black: BB,
+        side_to_move: Color,
+    ) -> Result<Self, BoardError<BB>> {
+        Board::from_parts_with_dimensions(size, size, white, black, side_to_move)
+    }
+
+    /// Creates a `Board` with a width and height from a custom position
+    ///
+    /// Fails like [`from_parts`](Board::from_parts)
+    pub fn from_parts_with_dimensions(
+        width: usize,
+        height: usize,
+        white: BB,
+        black: BB,
+        side_to_move: Color,
+    ) -> Result<Self, BoardError<BB>> {
+        for len in [width, height] {
+            if !(3..=BB::MAX_SIZE).contains(&len) {
+                return Err(BoardError::InvalidSize(len));
             }
+        }
 
-            f.write_fmt(format_args!("|{}\n", 8 - i))?;
-            // shift board up 1
-            white <<= 8;
-            black <<= 8;
+        let overlap = white & black;
+        if !overlap.is_empty() {
+            return Err(BoardError::Overlap(overlap));
         }
 
-        f.write_str("--------*\n")?;
-        f.write_str("abcdefgh\n")?;
-        f.write_str("01234567\n")?;
+        let outside = !BB::board_mask(width, height);
+        for (color, pawns) in [(Color::White, white), (Color::Black, black)] {
+            if !(pawns & outside).is_empty() {
+                return Err(BoardError::OffBoard {
+                    color,
+                    pawns: pawns & outside,
+                });
+            }
+        }
 
-        Ok(())
+        let mut board = Board {
+            white,
+            black,
+            side_to_move,
+            width,
+            height,
+            rules: Rules::STANDARD,
+            en_passant: None,
+            zobrist: 0,
+        };
+        board.zobrist = board.compute_zobrist();
+        // the side to move can't have already reached the far rank, the game would have ended before
+        if !(board.pieces_to_move() & board.win_rank(side_to_move)).is_empty() {
+            return Err(BoardError::AlreadyWon(side_to_move));
+        }
+
+        Ok(board)
     }
-}
 
-impl Board {
-    /// Creates a new `Board`
-    /// The size must be 3 to 8
-    pub fn new(size: usize) -> Self {
-        assert!(size > 2 && size < 9, "Invalid size, must be 3 to 8");
-        Board {
-            white: BitBoard(START_POS_WHITE[size - 1]),
-            black: BitBoard(START_POS_BLACK[size - 1]),
-            side_to_move: Color::White,
-            size,
+    /// Returns the `Board` played with `rules` instead.
+    /// A pending en passant capture is dropped if en passant isn't allowed
+    pub fn with_rules(mut self, rules: Rules) -> Self {
+        if rules.can_lose_after_move() {
+            LOSING_RULES.store(true, AtomicOrdering::Relaxed);
+        }
+        self.rules = rules;
+        if !rules.en_passant {
+            self.en_passant = None;
+        }
+        self.zobrist = self.compute_zobrist();
+        self
+    }
+
+    /// Returns the rule variants the board is played with
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    /// Returns the square the side to move can capture en passant into, if any
+    pub fn en_passant(&self) -> Option<BB::Square> {
+        self.en_passant.map(BB::square)
+    }
+
+    /// Returns the outcome of the game with the ranks white and black have to reach and the playing area,
+    /// so [`SizedBoard`](crate::sized_board::SizedBoard) can pass them as constants
+    pub(crate) fn outcome_with_ranks(
+        &self,
+        white_rank: BB,
+        black_rank: BB,
+        playable: BB,
+    ) -> Option<Outcome> {
+        // the player whose pawns reached the far rank, white first
+        let reached = if !(self.white & white_rank).is_empty() {
+            Some(Player::A)
+        } else if !(self.black & black_rank).is_empty() {
+            Some(Player::B)
+        } else {
+            None
+        };
+
+        match reached {
+            // reaching the far rank wins, or loses in misère
+            Some(player) if self.rules.misere => Some(Outcome::WonBy(player.other())),
+            Some(player) => Some(Outcome::WonBy(player)),
+            // if there are no moves, the stalemate rule decides
+            None if !MoveGen::with_playable_area(self, Mask::None, playable).has_any_move() => {
+                Some(self.stalemate_outcome())
+            }
+            // otherwise, the game is still ongoing
+            None => None,
+        }
+    }
+
+    /// Returns whether the side to move has any moves, without generating them all
+    pub fn has_any_move(&self) -> bool {
+        MoveGen::new(self).has_any_move()
+    }
+
+    /// Returns the outcome when the side to move has no moves
+    fn stalemate_outcome(&self) -> Outcome {
+        // in misère, having no moves wins
+        if self.rules.misere {
+            return Outcome::WonBy(self.next_player());
+        }
+
+        match self.rules.stalemate {
+            StalemateRule::Draw => Outcome::Draw,
+            StalemateRule::Loss => Outcome::WonBy(self.next_player().other()),
+            StalemateRule::Material => match self.white.pop_count().cmp(&self.black.pop_count()) {
+                Ordering::Greater => Outcome::WonBy(Player::A),
+                Ordering::Less => Outcome::WonBy(Player::B),
+                Ordering::Equal => Outcome::Draw,
+            },
+        }
+    }
+
+    /// Returns the index of the pawn captured by a move, if any
+    fn captured_index(&self, mv: Move<BB>) -> Option<usize> {
+        let dest = mv.dest_index();
+        if !(self.pieces_not_to_move() & BB::from_index(dest)).is_empty() {
+            Some(dest)
+        // a diagonal move into the skipped square captures the pawn that double stepped past it
+        } else if self.en_passant == Some(dest) && mv.src_index() % BB::STRIDE != dest % BB::STRIDE
+        {
+            match self.side_to_move {
+                Color::White => Some(dest - BB::STRIDE),
+                Color::Black => Some(dest + BB::STRIDE),
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Returns the [Zobrist key](https://www.chessprogramming.org/Zobrist_Hashing) of the position
+    pub fn zobrist(&self) -> u64 {
+        self.zobrist
+    }
+
+    /// Computes the Zobrist key from scratch.
+    /// [`zobrist`](Board::zobrist) is updated incrementally instead
+    pub fn compute_zobrist(&self) -> u64 {
+        let mut zobrist = match self.side_to_move {
+            Color::White => 0,
+            Color::Black => ZOBRIST_BLACK_TO_MOVE,
+        };
+        for index in self.white.indices() {
+            zobrist ^= ZOBRIST_WHITE[index];
+        }
+        for index in self.black.indices() {
+            zobrist ^= ZOBRIST_BLACK[index];
+        }
+        if let Some(index) = self.en_passant {
+            zobrist ^= ZOBRIST_EN_PASSANT[index];
+        }
+        zobrist
+    }
+
+    /// Returns the Zobrist keys of a color's pawns
+    fn zobrist_keys(color: Color) -> &'static [u64; 128] {
+        match color {
+            Color::White => &ZOBRIST_WHITE,
+            Color::Black => &ZOBRIST_BLACK,
         }
     }
 
-    /// Returns the `BitBoard` of a certain color's bitboard
-    pub fn pieces(&self, color: Color) -> BitBoard {
+    /// Returns the rank a color has to reach to win
+    fn win_rank(&self, color: Color) -> BB {
+        match color {
+            Color::White => BB::rank(self.height - 1),
+            Color::Black => BB::rank(0),
+        }
+    }
+
+    /// Returns the bitboard of a certain color's bitboard
+    pub fn pieces(&self, color: Color) -> BB {
         match color {
             Color::White => self.white,
             Color::Black => self.black,
         }
     }
 
-    /// Returns a mutable reference to a certain color's `BitBoard`
-    pub fn pieces_mut(&mut self, color: Color) -> &mut BitBoard {
+    /// Returns a mutable reference to a certain color's bitboard.
+    /// The Zobrist key isn't updated
+    fn pieces_mut(&mut self, color: Color) -> &mut BB {
         match color {
             Color::White => &mut self.white,
             Color::Black => &mut self.black,
         }
     }
 
-    /// Returns the `BitBoard` of the pieces to move
-    pub fn pieces_to_move(&self) -> BitBoard {
+    /// Returns the bitboard of the pieces to move
+    pub fn pieces_to_move(&self) -> BB {
         self.pieces(self.side_to_move)
     }
 
-    /// Returns a mutable reference to the `BitBoard` of the pieces to move
-    pub fn pieces_to_move_mut(&mut self) -> &mut BitBoard {
+    /// Returns a mutable reference to the bitboard of the pieces to move.
+    /// The Zobrist key isn't updated
+    fn pieces_to_move_mut(&mut self) -> &mut BB {
         self.pieces_mut(self.side_to_move)
     }
 
-    /// Returns the `BitBoard` of the pieces not to move
-    pub fn pieces_not_to_move(&self) -> BitBoard {
+    /// Returns the bitboard of the pieces not to move
+    pub fn pieces_not_to_move(&self) -> BB {
         self.pieces(!self.side_to_move)
     }
 
-    /// Returns a mutable reference to the `BitBoard` of the pieces not to move
-    pub fn pieces_not_to_move_mut(&mut self) -> &mut BitBoard {
+    /// Returns a mutable reference to the bitboard of the pieces not to move.
+    /// The Zobrist key isn't updated
+    fn pieces_not_to_move_mut(&mut self) -> &mut BB {
         self.pieces_mut(!self.side_to_move)
     }
 
@@ -127,21 +359,384 @@ impl Board {
         self.side_to_move
     }
 
-    /// Returns a `BitBoard` of all empty squares
-    pub fn empty(&self) -> BitBoard {
+    /// Returns a bitboard of all empty squares
+    pub fn empty(&self) -> BB {
         !self.occupied()
     }
 
-    /// Returns a `BitBoard` of all occupied squares
-    pub fn occupied(&self) -> BitBoard {
+    /// Returns a bitboard of all occupied squares
+    pub fn occupied(&self) -> BB {
         self.white | self.black
     }
+
+    /// Returns the width of the board, from 3 to `BB::MAX_SIZE`
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of the board, from 3 to `BB::MAX_SIZE`
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns a bitboard of the playing area
+    pub fn mask(&self) -> BB {
+        BB::board_mask(self.width, self.height)
+    }
+
+    /// Plays a move, returning the information needed to undo it with [`unmake`](Board::unmake)
+    pub fn make(&mut self, mv: Move<BB>) -> Undo {
+        let undo = Undo {
+            captured: self.captured_index(mv),
+            en_passant: self.en_passant,
+            zobrist: self.zobrist,
+        };
+        self.play(mv);
+        undo
+    }
+
+    /// Undoes a move played by [`make`](Board::make), restoring any captured pawn and the side to move
+    pub fn unmake(&mut self, mv: Move<BB>, undo: Undo) {
+        let src_bb = BB::from_index(mv.src_index());
+        let dest_bb = BB::from_index(mv.dest_index());
+        self.side_to_move = !self.side_to_move; // Switch back the side to move
+        *self.pieces_to_move_mut() ^= src_bb | dest_bb; // Move the pawn back
+        if let Some(captured) = undo.captured {
+            *self.pieces_not_to_move_mut() |= BB::from_index(captured); // Restore the captured pawn
+        }
+        self.en_passant = undo.en_passant;
+        self.zobrist = undo.zobrist;
+    }
+
+    /// Parses a move like `a1a2` and checks that it's legal in this position
+    pub fn parse_move(&self, s: &str) -> Result<Move<BB>, MoveError<BB>> {
+        let mv: Move<BB> = s.parse()?;
+
+        for sq in [mv.src(), mv.dest()] {
+            if (BB::from_index(BB::index(sq)) & self.mask()).is_empty() {
+                return Err(MoveError::OffBoard(sq));
+            }
+        }
+
+        if MoveGen::new(self).any(|x| x == mv) {
+            Ok(mv)
+        } else {
+            Err(MoveError::Illegal(mv))
+        }
+    }
+
+    /// Returns the position in text notation, similar to [FEN](https://www.chessprogramming.org/Forsyth-Edwards_Notation)
+    ///
+    /// Ranks are listed from the top rank down to rank 1 and separated by `/`.
+    /// `P` is a white pawn, `p` is a black pawn and a number is a run of empty squares.
+    /// The placement is followed by the side to move, `w` or `b`.
+    /// The starting position of a 3x3 board is `ppp/3/PPP w`, and of a 4x3 board is `pppp/4/PPPP w`.
+    /// The [`Rules`](Rules) and any en passant square aren't part of the notation
+    pub fn to_notation(&self) -> String {
+        let mut notation = String::new();
+
+        for rank in (0..self.height).rev() {
+            let mut empty = 0;
+            for file in 0..self.width {
+                let bb = BB::from_index(rank * BB::STRIDE + file);
+                let piece = if !(self.white & bb).is_empty() {
+                    'P'
+                } else if !(self.black & bb).is_empty() {
+                    'p'
+                } else {
+                    empty += 1;
+                    continue;
+                };
+
+                // write out the run of empty squares before the pawn
+                if empty != 0 {
+                    notation.push_str(&empty.to_string());
+                    empty = 0;
+                }
+                notation.push(piece);
+            }
+
+            if empty != 0 {
+                notation.push_str(&empty.to_string());
+            }
+            if rank != 0 {
+                notation.push('/');
+            }
+        }
+
+        notation.push(' ');
+        notation.push(match self.side_to_move {
+            Color::White => 'w',
+            Color::Black => 'b',
+        });
+
+        notation
+    }
+}
+
+/// The information needed to undo a move, returned by [`Board::make`](Board::make)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Undo {
+    /// The index of the captured pawn, if any
+    captured: Option<usize>,
+    /// The en passant square before the move
+    en_passant: Option<usize>,
+    /// The Zobrist key before the move
+    zobrist: u64,
+}
+
+/// An error from parsing a [`Board`](Board) from text notation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseBoardError<BB: BitBoardBackend = BitBoard> {
+    /// The notation didn't have exactly two fields, the placement and the side to move
+    WrongFieldCount(usize),
+    /// The amount of ranks or files isn't a valid size
+    InvalidSize(usize),
+    /// A character that isn't a pawn or a number
+    InvalidPiece(char),
+    /// A rank (from 1) doesn't have as many squares as the top rank
+    WrongRankLength {
+        /// The rank, from 1
+        rank: usize,
+        /// The amount of squares in the rank
+        len: usize,
+    },
+    /// The side to move isn't `w` or `b`
+    InvalidSideToMove(String),
+    /// The position isn't valid
+    InvalidPosition(BoardError<BB>),
+}
+
+impl<BB: BitBoardBackend> Display for ParseBoardError<BB> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseBoardError::WrongFieldCount(count) => write!(
+                f,
+                "expected a placement and a side to move, got {count} fields"
+            ),
+            ParseBoardError::InvalidSize(size) => {
+                write!(f, "invalid size {size}, must be 3 to {}", BB::MAX_SIZE)
+            }
+            ParseBoardError::InvalidPiece(c) => write!(f, "invalid piece '{c}'"),
+            ParseBoardError::WrongRankLength { rank, len } => {
+                write!(f, "rank {rank} has {len} squares")
+            }
+            ParseBoardError::InvalidSideToMove(side) => {
+                write!(f, "invalid side to move '{side}', must be 'w' or 'b'")
+            }
+            ParseBoardError::InvalidPosition(err) => write!(f, "invalid position: {err}"),
+        }
+    }
+}
+
+impl<BB: BitBoardBackend> Error for ParseBoardError<BB> {}
+
+impl<BB: BitBoardBackend> From<BoardError<BB>> for ParseBoardError<BB> {
+    fn from(err: BoardError<BB>) -> Self {
+        ParseBoardError::InvalidPosition(err)
+    }
+}
+
+/// An error from creating a [`Board`](Board) from a custom position
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoardError<BB: BitBoardBackend = BitBoard> {
+    /// The width or height isn't 3 to `BB::MAX_SIZE`
+    InvalidSize(usize),
+    /// White and black pawns are on the same squares
+    Overlap(BB),
+    /// Pawns are outside of the board
+    OffBoard {
+        /// The color of the pawns
+        color: Color,
+        /// The pawns outside of the board
+        pawns: BB,
+    },
+    /// The side to move already reached the far rank, so the game already ended
+    AlreadyWon(Color),
+}
+
+impl<BB: BitBoardBackend> Display for BoardError<BB> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BoardError::InvalidSize(size) => {
+                write!(f, "invalid size {size}, must be 3 to {}", BB::MAX_SIZE)
+            }
+            BoardError::Overlap(squares) => {
+                write!(
+                    f,
+                    "white and black pawns overlap on {}",
+                    squares_str(*squares)
+                )
+            }
+            BoardError::OffBoard { color, pawns } => write!(
+                f,
+                "{color:?} pawns are outside of the board on {}",
+                squares_str(*pawns)
+            ),
+            BoardError::AlreadyWon(color) => {
+                write!(f, "{color:?} already reached the far rank but is to move")
+            }
+        }
+    }
+}
+
+impl<BB: BitBoardBackend> Error for BoardError<BB> {}
+
+/// Lists the squares of a bitboard, separated by spaces
+fn squares_str<BB: BitBoardBackend>(bb: BB) -> String {
+    bb.indices()
+        .map(|index| BB::square(index).to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl<BB: BitBoardBackend> FromStr for Board<BB> {
+    type Err = ParseBoardError<BB>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        let (placement, side) = match fields[..] {
+            [placement, side] => (placement, side),
+            _ => return Err(ParseBoardError::WrongFieldCount(fields.len())),
+        };
+
+        let ranks: Vec<&str> = placement.split('/').collect();
+        let height = ranks.len();
+        if !(3..=BB::MAX_SIZE).contains(&height) {
+            return Err(ParseBoardError::InvalidSize(height));
+        }
+
+        let mut white = BB::EMPTY;
+        let mut black = BB::EMPTY;
+        // the width is set by the top rank
+        let mut width = None;
+        // ranks are listed from the top down
+        for (rank, pieces) in (0..height).rev().zip(ranks) {
+            let mut file = 0;
+            let mut empty = 0;
+            for c in pieces.chars() {
+                // runs of empty squares can be more than one digit
+                if let Some(digit) = c.to_digit(10) {
+                    empty = empty * 10 + digit as usize;
+                    // also keeps long runs from overflowing
+                    let len = file + empty;
+                    if len > BB::MAX_SIZE {
+                        return Err(match width {
+                            None => ParseBoardError::InvalidSize(len),
+                            Some(_) => ParseBoardError::WrongRankLength {
+                                rank: rank + 1,
+                                len,
+                            },
+                        });
+                    }
+                    continue;
+                }
+                file += empty;
+                empty = 0;
+
+                let pawns = match c {
+                    'P' => &mut white,
+                    'p' => &mut black,
+                    _ => return Err(ParseBoardError::InvalidPiece(c)),
+                };
+                if file < BB::STRIDE {
+                    *pawns |= BB::from_index(rank * BB::STRIDE + file);
+                }
+                file += 1;
+            }
+            file += empty;
+
+            match width {
+                None if !(3..=BB::MAX_SIZE).contains(&file) => {
+                    return Err(ParseBoardError::InvalidSize(file))
+                }
+                None => width = Some(file),
+                Some(width) if file != width => {
+                    return Err(ParseBoardError::WrongRankLength {
+                        rank: rank + 1,
+                        len: file,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        let side_to_move = match side {
+            "w" => Color::White,
+            "b" => Color::Black,
+            _ => return Err(ParseBoardError::InvalidSideToMove(side.to_string())),
+        };
+
+        Ok(Board::from_parts_with_dimensions(
+            width.unwrap(),
+            height,
+            white,
+            black,
+            side_to_move,
+        )?)
+    }
+}
+
+impl<BB: BitBoardBackend> Board<BB> {
+    /// Mirrors a bitboard across the vertical axis of the board
+    fn mirror_bb(&self, bb: BB) -> BB {
+        bb.mirror(self.width) & self.mask()
+    }
+
+    /// Mirrors a square across the vertical axis of the board
+    fn mirror_square(&self, sq: BB::Square) -> BB::Square {
+        let index = BB::index(sq);
+        let (file, rank) = (index % BB::STRIDE, index / BB::STRIDE);
+        BB::square(rank * BB::STRIDE + self.width - 1 - file)
+    }
+
+    /// Returns the canonical version of this position, shared by all of its symmetries,
+    /// and the symmetry that maps this position to it
+    pub fn canonical(&self) -> (Self, D1Symmetry) {
+        let mirrored = self.map(D1Symmetry::new(true));
+        if (mirrored.white, mirrored.black) < (self.white, self.black) {
+            (mirrored, D1Symmetry::new(true))
+        } else {
+            (self.clone(), D1Symmetry::new(false))
+        }
+    }
 }
 
-impl UnitSymmetryBoard for Board {}
+impl<BB: BitBoardBackend> BoardSymmetry<Board<BB>> for Board<BB> {
+    type Symmetry = D1Symmetry;
+
+    fn map(&self, sym: Self::Symmetry) -> Self {
+        if !sym.mirror {
+            return self.clone();
+        }
+
+        let mut board = Board {
+            white: self.mirror_bb(self.white),
+            black: self.mirror_bb(self.black),
+            side_to_move: self.side_to_move,
+            width: self.width,
+            height: self.height,
+            rules: self.rules,
+            en_passant: self
+                .en_passant
+                .map(|index| BB::index(self.mirror_square(BB::square(index)))),
+            zobrist: 0,
+        };
+        board.zobrist = board.compute_zobrist();
+        board
+    }
+
+    fn map_move(&self, sym: Self::Symmetry, mv: Move<BB>) -> Move<BB> {
+        if !sym.mirror {
+            return mv;
+        }
+
+        Move::from_squares(self.mirror_square(mv.src()), self.mirror_square(mv.dest()))
+    }
+}
 
-impl BoardTrait for Board {
-    type Move = Move;
+impl<BB: BitBoardBackend> BoardTrait for Board<BB> {
+    type Move = Move<BB>;
 
     fn next_player(&self) -> board_game::board::Player {
         match self.side_to_move {
@@ -156,41 +751,92 @@ impl BoardTrait for Board {
 
     fn play(&mut self, mv: Self::Move) {
         // Convert the squares to bitboards
-        let src_bb = BitBoard::from_square(mv.src());
-        let dest_bb = BitBoard::from_square(mv.dest());
+        let src_bb = BB::from_index(mv.src_index());
+        let dest_bb = BB::from_index(mv.dest_index());
+
+        // Update the Zobrist key for the moved pawn, the captured pawn and the side to move
+        let keys = Self::zobrist_keys(self.side_to_move);
+        self.zobrist ^= keys[mv.src_index()] ^ keys[mv.dest_index()];
+        let captured = self.captured_index(mv);
+        if let Some(captured) = captured {
+            self.zobrist ^= Self::zobrist_keys(!self.side_to_move)[captured];
+        }
+        self.zobrist ^= ZOBRIST_BLACK_TO_MOVE;
+
+        // A double step can be captured en passant on the next move
+        if let Some(index) = self.en_passant {
+            self.zobrist ^= ZOBRIST_EN_PASSANT[index];
+        }
+        self.en_passant = None;
+        if self.rules.en_passant && mv.src_index().abs_diff(mv.dest_index()) == 2 * BB::STRIDE {
+            let skipped = (mv.src_index() + mv.dest_index()) / 2;
+            self.zobrist ^= ZOBRIST_EN_PASSANT[skipped];
+            self.en_passant = Some(skipped);
+        }
+
         *self.pieces_to_move_mut() ^= src_bb | dest_bb; // Move the pawn
-        *self.pieces_not_to_move_mut() &= !dest_bb; // Remove the captured pawn
+        if let Some(captured) = captured {
+            *self.pieces_not_to_move_mut() &= !BB::from_index(captured); // Remove the captured pawn
+        }
         self.side_to_move = !self.side_to_move; // Switch sides to move
     }
 
     fn outcome(&self) -> Option<board_game::board::Outcome> {
-        // if white's pawns reaches black's starting rank, white wins
-        if self.white & BitBoard(RANKS[self.size - 1]) != EMPTY_BB {
-            Some(Outcome::WonBy(Player::A))
-        // if white's pawns reaches black's starting rank, white wins
-        } else if self.black & BitBoard(RANKS[0]) != EMPTY_BB {
-            Some(Outcome::WonBy(Player::B))
-        // if there are no moves, it's a draw
-        } else if MoveGen::new(self).len() == 0 {
-            Some(Outcome::Draw)
-        // otherwise, the game is still ongoing
-        } else {
-            None
-        }
+        self.outcome_with_ranks(
+            self.win_rank(Color::White),
+            self.win_rank(Color::Black),
+            self.mask(),
+        )
     }
 
     fn can_lose_after_move() -> bool {
-        false
+        // standard hexapawn can only be won by the player who moves
+        LOSING_RULES.load(AtomicOrdering::Relaxed)
+    }
+}
+
+impl<BB: BitBoardBackend> PawnBoard for Board<BB> {
+    type BB = BB;
+
+    fn pieces(&self, color: Color) -> BB {
+        Board::pieces(self, color)
+    }
+
+    fn side_to_move(&self) -> Color {
+        self.side_to_move
+    }
+
+    fn mask(&self) -> BB {
+        Board::mask(self)
+    }
+
+    fn move_gen(&self, mask: Mask) -> MoveGen<BB> {
+        MoveGen::with_mask(self, mask)
+    }
+
+    fn win_rank(&self, color: Color) -> BB {
+        Board::win_rank(self, color)
+    }
+
+    fn capture_squares(&self) -> BB {
+        match self.en_passant() {
+            Some(sq) => self.pieces_not_to_move() | BB::from_index(BB::index(sq)),
+            None => self.pieces_not_to_move(),
+        }
+    }
+
+    fn misere(&self) -> bool {
+        self.rules.misere
     }
 }
 
-impl<'a> BoardMoves<'a, Board> for Board {
-    type AllMovesIterator = AllMoves;
+impl<'a, BB: BitBoardBackend> BoardMoves<'a, Board<BB>> for Board<BB> {
+    type AllMovesIterator = AllMoves<BB>;
 
-    type AvailableMovesIterator = Internal<MoveGen>;
+    type AvailableMovesIterator = Internal<MoveGen<BB>>;
 
     fn all_possible_moves() -> Self::AllMovesIterator {
-        AllMoves
+        AllMoves::default()
     }
 
     fn available_moves(&'a self) -> Self::AvailableMovesIterator {
@@ -199,18 +845,20 @@ impl<'a> BoardMoves<'a, Board> for Board {
 }
 
 #[doc(hidden)]
-pub struct AllMoves;
+#[derive(Default)]
+pub struct AllMoves<BB: BitBoardBackend>(PhantomData<BB>);
 
-impl InternalIterator for AllMoves {
-    type Item = Move;
+impl<BB: BitBoardBackend> InternalIterator for AllMoves<BB> {
+    type Item = Move<BB>;
 
     fn try_for_each<R, F>(self, mut f: F) -> std::ops::ControlFlow<R>
     where
         F: FnMut(Self::Item) -> std::ops::ControlFlow<R>,
     {
-        for from in chess::ALL_SQUARES {
-            for to in chess::ALL_SQUARES {
-                f(Move::new(from, to))?;
+        let squares = BB::STRIDE * BB::MAX_SIZE;
+        for from in 0..squares {
+            for to in 0..squares {
+                f(Move::from_squares(BB::square(from), BB::square(to)))?;
             }
         }
 
@@ -0,0 +1,347 @@
+//! The [`AlphaBetaBot`](AlphaBetaBot), a native alpha-beta search with iterative deepening
+
+use std::fmt::{Debug, Formatter};
+use std::time::Instant;
+
+use board_game::{
+    ai::{minimax::Heuristic, Bot},
+    board::Board as BoardTrait,
+};
+
+use crate::{
+    board::Board,
+    clock::{Clock, TimeControl},
+    move_gen::Move,
+    move_order::{History, Killers, MoveOrder, Stage, StagedMoveGen},
+    solver::Bound,
+};
+
+/// A score beyond every heuristic value
+const INFINITY: i32 = i32::MAX;
+/// Scores beyond this are wins or losses, like [`SolverHeuristicSimplified`](crate::bot::heuristic::SolverHeuristicSimplified)'s
+const DECIDED: i32 = i32::MAX - 10_000;
+/// The amount of entries in the transposition table
+const TABLE_SIZE: usize = 1 << 16;
+/// How many positions are searched between checks of the time, which is slower than searching a position
+const TIME_CHECK_INTERVAL: u64 = 1024;
+
+/// A move to search and whether it's a push, the only moves killers and history order
+type OrderedMove = (Move, bool);
+
+/// An entry of the transposition table
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    /// The Zobrist key of the position
+    key: u64,
+    /// How deep the position was searched
+    depth: u32,
+    /// The score, counting wins and losses from this position instead of from the root
+    score: i32,
+    /// Whether the score is exact or a bound
+    bound: Bound,
+    /// The best move found
+    best_move: Option<Move>,
+}
+
+/// Negamax with alpha-beta pruning and iterative deepening, limited by depth and optionally by nodes and a [`Clock`](Clock).
+///
+/// A Zobrist-keyed transposition table is kept between iterations and moves,
+/// and each iteration searches the previous iteration's principal variation first.
+/// Within the stages of [`StagedMoveGen`](StagedMoveGen), pushes are ordered by [`Killers`](Killers)
+/// and [`History`](History)
+pub struct AlphaBetaBot<H: Heuristic<Board, V = i32>> {
+    /// The deepest iteration
+    max_depth: u32,
+    /// The amount of positions to search for a move before stopping, if any
+    node_limit: Option<u64>,
+    /// The clock, if the search is also limited by time
+    clock: Option<Clock>,
+    /// When the search for the current move has to stop, if there's a clock
+    deadline: Option<Instant>,
+    /// The heuristic for positions at the end of the search
+    heuristic: H,
+    /// The transposition table, indexed by Zobrist key
+    table: Vec<Option<Entry>>,
+    /// The principal variation of each ply of the current iteration
+    pv_lines: Vec<Vec<Move>>,
+    /// The ordered moves of each ply, kept so they're allocated once
+    move_lists: Vec<Vec<OrderedMove>>,
+    /// The pushes that caused a cutoff at each ply
+    killers: Killers,
+    /// How often each push caused a cutoff, kept between moves
+    history: History,
+    /// The principal variation of the last completed iteration
+    pv: Vec<Move>,
+    /// The depth of the last completed iteration
+    depth: u32,
+    /// The amount of positions searched for the last move
+    nodes: u64,
+}
+
+impl<H: Heuristic<Board, V = i32>> Debug for AlphaBetaBot<H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "AlphaBetaBot {{ depth: {}, node_limit: {:?}, heuristic: {:?} }}",
+            self.max_depth, self.node_limit, self.heuristic
+        )
+    }
+}
+
+impl<H: Heuristic<Board, V = i32>> AlphaBetaBot<H> {
+    /// Creates a new [`AlphaBetaBot`](AlphaBetaBot) that searches up to `max_depth` plies
+    pub fn new(max_depth: u32, heuristic: H) -> Self {
+        assert!(max_depth > 0);
+        AlphaBetaBot {
+            max_depth,
+            node_limit: None,
+            clock: None,
+            deadline: None,
+            heuristic,
+            table: vec![None; TABLE_SIZE],
+            pv_lines: vec![],
+            move_lists: vec![],
+            killers: Killers::new(),
+            history: History::new(),
+            pv: vec![],
+            depth: 0,
+            nodes: 0,
+        }
+    }
+
+    /// Stops searching for a move after `node_limit` positions, playing the best move of the last completed iteration
+    pub fn with_node_limit(mut self, node_limit: u64) -> Self {
+        self.node_limit = Some(node_limit);
+        self
+    }
+
+    /// Stops searching for a move when the time for the move runs out.
+    /// Like the node limit, it plays the best move of the last completed iteration.
+    /// The [`Clock`](Clock) runs over every move, so the bot plays one game with it
+    pub fn with_time_control(mut self, time_control: TimeControl) -> Self {
+        self.clock = Some(Clock::new(time_control));
+        self
+    }
+
+    /// Returns the clock, if the search is also limited by time
+    pub fn clock(&self) -> Option<&Clock> {
+        self.clock.as_ref()
+    }
+
+    /// Returns the principal variation of the last completed iteration
+    pub fn principal_variation(&self) -> &[Move] {
+        &self.pv
+    }
+
+    /// Returns the depth of the last completed iteration
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Returns the amount of positions searched for the last move
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    /// Searches a position with iterative deepening and returns the best move
+    fn search_root(&mut self, board: &mut Board) -> Move {
+        let start = Instant::now();
+        self.deadline = self.clock.as_ref().map(|clock| clock.deadline(start));
+        self.pv.clear();
+        self.depth = 0;
+        self.nodes = 0;
+        // killers are for the plies of this position, history counts older cutoffs less
+        self.killers = Killers::new();
+        self.history.age();
+
+        for depth in 1..=self.max_depth {
+            if self.depth > 0
+                && self
+                    .deadline
+                    .is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                break;
+            }
+            let Some(score) = self.search(board, depth, 0, -INFINITY, INFINITY, true) else {
+                break;
+            };
+            self.pv = self.pv_lines[0].clone();
+            self.depth = depth;
+            // a proven result doesn't change with more depth
+            if score.abs() > DECIDED {
+                break;
+            }
+        }
+
+        if let Some(clock) = &mut self.clock {
+            clock.record(start.elapsed());
+        }
+        self.pv[0]
+    }
+
+    /// Returns whether the search has to stop, because of the node limit or the clock
+    // `is_multiple_of` would need Rust 1.87
+    #[allow(clippy::manual_is_multiple_of)]
+    fn out_of_budget(&self) -> bool {
+        self.node_limit.is_some_and(|limit| self.nodes > limit)
+            || (self.nodes % TIME_CHECK_INTERVAL == 0
+                && self
+                    .deadline
+                    .is_some_and(|deadline| Instant::now() >= deadline))
+    }
+
+    /// Searches a position `ply` plies from the root to `depth` more plies.
+    /// `on_pv` is whether the moves so far are the previous principal variation.
+    /// Returns `None` if the node limit was reached
+    fn search(
+        &mut self,
+        board: &mut Board,
+        depth: u32,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+        on_pv: bool,
+    ) -> Option<i32> {
+        self.nodes += 1;
+        // the first iteration always completes, so there's a move to play
+        if self.depth > 0 && self.out_of_budget() {
+            return None;
+        }
+
+        if self.pv_lines.len() <= ply {
+            self.pv_lines.resize(ply + 1, vec![]);
+            self.move_lists.resize(ply + 1, vec![]);
+        }
+        self.pv_lines[ply].clear();
+
+        if depth == 0 || board.is_done() {
+            return Some(self.heuristic.value(board, ply as u32));
+        }
+
+        let key = board.zobrist();
+        let index = key as usize % TABLE_SIZE;
+        let mut tt_move = None;
+        if let Some(entry) = self.table[index].filter(|entry| entry.key == key) {
+            let score = from_table(entry.score, ply);
+            // the root is always searched, so it has a principal variation
+            if ply > 0 && entry.depth >= depth {
+                match entry.bound {
+                    Bound::Exact => return Some(score),
+                    Bound::Lower if score >= beta => return Some(score),
+                    Bound::Upper if score <= alpha => return Some(score),
+                    _ => {}
+                }
+            }
+            // the key doesn't cover the size or rules, so a colliding entry could hold an illegal move
+            tt_move = entry.best_move.filter(|&mv| board.is_available_move(mv));
+        }
+
+        // the previous principal variation first, then the best move from the table,
+        // then wins, captures and pushes, with pushes ordered by killers and history
+        let pv_move = self.pv.get(ply).copied().filter(|_| on_pv);
+        let first = [pv_move, tt_move.filter(|&mv| Some(mv) != pv_move)];
+        let mut moves = std::mem::take(&mut self.move_lists[ply]);
+        moves.clear();
+        moves.extend(first.into_iter().flatten().map(|mv| (mv, false)));
+        // history scores saturate at i32::MAX, so the sum has to saturate too
+        let order = |mv| {
+            self.killers
+                .score(ply, mv)
+                .saturating_add(self.history.score(mv))
+        };
+        let mut staged = StagedMoveGen::with_order(&*board, &order);
+        while let Some(mv) = staged.next() {
+            if !first.contains(&Some(mv)) {
+                moves.push((mv, staged.stage() == Stage::Pushes));
+            }
+        }
+
+        let original_alpha = alpha;
+        let mut best = (-INFINITY, None);
+        let mut result = Some(());
+        for &(mv, push) in &moves {
+            let undo = board.make(mv);
+            let score = self.search(
+                board,
+                depth - 1,
+                ply + 1,
+                -beta,
+                -alpha,
+                on_pv && Some(mv) == pv_move,
+            );
+            board.unmake(mv, undo);
+            let Some(score) = score.map(|score| -score) else {
+                result = None;
+                break;
+            };
+
+            if score > best.0 {
+                best = (score, Some(mv));
+            }
+            if score > alpha {
+                alpha = score;
+                // this move and the principal variation after it
+                let line = std::mem::take(&mut self.pv_lines[ply + 1]);
+                let pv = &mut self.pv_lines[ply];
+                pv.clear();
+                pv.push(mv);
+                pv.extend(line);
+            }
+            if alpha >= beta {
+                if push {
+                    self.killers.store(ply, mv);
+                    self.history.add(mv, depth);
+                }
+                break;
+            }
+        }
+        self.move_lists[ply] = moves;
+        result?;
+
+        let (score, best_move) = best;
+        let bound = if score <= original_alpha {
+            Bound::Upper
+        } else if score >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table[index] = Some(Entry {
+            key,
+            depth,
+            score: to_table(score, ply),
+            bound,
+            best_move,
+        });
+        Some(score)
+    }
+}
+
+impl<B, H> Bot<B> for AlphaBetaBot<H>
+where
+    B: BoardTrait<Move = Move> + Into<Board>,
+    H: Heuristic<Board, V = i32>,
+{
+    fn select_move(&mut self, board: &B) -> B::Move {
+        assert!(!board.is_done());
+        self.search_root(&mut board.clone().into())
+    }
+}
+
+/// Converts a score counting from the root to one counting from the position `ply` plies deep
+fn to_table(score: i32, ply: usize) -> i32 {
+    match score {
+        score if score > DECIDED => score + ply as i32,
+        score if score < -DECIDED => score - ply as i32,
+        score => score,
+    }
+}
+
+/// Converts a score counting from the position `ply` plies deep to one counting from the root
+fn from_table(score: i32, ply: usize) -> i32 {
+    match score {
+        score if score > DECIDED => score - ply as i32,
+        score if score < -DECIDED => score + ply as i32,
+        score => score,
+    }
+}
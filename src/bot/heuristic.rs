@@ -10,52 +10,56 @@ use board_game::{
 use chess::Color;
 use rand::{prelude::IteratorRandom, Rng};
 
-use crate::{
-    board::Board,
-    move_gen::{Mask, MoveGen},
-};
+use crate::{bitboard::BitBoardBackend, move_gen::Mask, pawn_board::PawnBoard};
 
 /// North [pawn fill](https://www.chessprogramming.org/Pawn_Fills)
 /// using parallel prefix [Kogge-Stone routines](https://www.chessprogramming.org/Kogge-Stone_Algorithm)
 #[allow(non_snake_case)]
-pub fn N_fill(mut bb: u64) -> u64 {
-    bb |= bb << 8;
-    bb |= bb << 16;
-    bb |= bb << 32;
+pub fn N_fill<BB: BitBoardBackend>(mut bb: BB) -> BB {
+    // shifts by 1, 2, 4, ... ranks until every rank is covered
+    let mut shift = BB::STRIDE;
+    while shift < BB::STRIDE * BB::MAX_SIZE {
+        bb |= bb.shift_up(shift);
+        shift *= 2;
+    }
     bb
 }
 
 /// South [pawn fill](https://www.chessprogramming.org/Pawn_Fills)
 /// using parallel prefix [Kogge-Stone routines](https://www.chessprogramming.org/Kogge-Stone_Algorithm)
 #[allow(non_snake_case)]
-pub fn S_fill(mut bb: u64) -> u64 {
-    bb |= bb >> 8;
-    bb |= bb >> 16;
-    bb |= bb >> 32;
+pub fn S_fill<BB: BitBoardBackend>(mut bb: BB) -> BB {
+    // shifts by 1, 2, 4, ... ranks until every rank is covered
+    let mut shift = BB::STRIDE;
+    while shift < BB::STRIDE * BB::MAX_SIZE {
+        bb |= bb.shift_down(shift);
+        shift *= 2;
+    }
     bb
 }
 
 /// Returns an evaluation of how far the pawns are by counting the [rear-fill](https://www.chessprogramming.org/Pawn_Fills)
-pub fn advancement_eval(bb: u64, color: Color) -> u32 {
+/// inside of the playing area `mask`
+pub fn advancement_eval<BB: BitBoardBackend>(bb: BB, color: Color, mask: BB) -> u32 {
     match color {
-        Color::White => S_fill(bb).count_ones(),
-        Color::Black => N_fill(bb).count_ones(),
+        Color::White => (S_fill(bb) & mask).pop_count(),
+        Color::Black => (N_fill(bb) & mask).pop_count(),
     }
 }
 
 /// Returns an evaluation of the amount of pawns by counting the [population count](https://www.chessprogramming.org/Population_Count)
-pub fn material_eval(bb: u64) -> u32 {
-    bb.count_ones()
+pub fn material_eval<BB: BitBoardBackend>(bb: BB) -> u32 {
+    bb.pop_count()
 }
 
 #[derive(Debug, Clone)]
 /// A simplified [`SolverHeuristic`](SolverHeuristic) by converting to i32
 pub struct SolverHeuristicSimplified;
 
-impl Heuristic<Board> for SolverHeuristicSimplified {
+impl<B: BoardTrait> Heuristic<B> for SolverHeuristicSimplified {
     type V = i32;
 
-    fn value(&self, board: &Board, depth: u32) -> Self::V {
+    fn value(&self, board: &B, depth: u32) -> Self::V {
         SolverHeuristic.value(board, depth).to_i32()
     }
 
@@ -64,22 +68,33 @@ impl Heuristic<Board> for SolverHeuristicSimplified {
     }
 }
 
+/// Returns 1 when the objective is to reach the far rank, -1 when it's inverted in [misère](crate::rules::Rules::misere)
+fn objective<B: PawnBoard>(board: &B) -> i32 {
+    if board.misere() {
+        -1
+    } else {
+        1
+    }
+}
+
 #[derive(Debug, Clone)]
-/// Returns an evaluation of \# player's pawns - \# opponent's pawns
+/// Returns an evaluation of \# player's pawns - \# opponent's pawns, negated in misère
 pub struct MaterialHeuristic;
 
-impl Heuristic<Board> for MaterialHeuristic {
+impl<B: PawnBoard> Heuristic<B> for MaterialHeuristic {
     type V = i32;
 
-    fn value(&self, board: &Board, depth: u32) -> Self::V {
+    fn value(&self, board: &B, depth: u32) -> Self::V {
         // if the board is done, it's infinity for winning, negative infinity for losing
         if board.is_done() {
             return SolverHeuristicSimplified.value(board, depth);
         }
 
-        // return the difference between the amount of the player's pawns and the amount of the opponent's pawns
-        material_eval(board.pieces_to_move().0) as i32
-            - material_eval(board.pieces_not_to_move().0) as i32
+        // return the difference between the amount of the player's pawns and the amount of the opponent's pawns,
+        // in misère having fewer pawns means fewer moves, which is closer to winning
+        objective(board)
+            * (material_eval(board.pieces_to_move()) as i32
+                - material_eval(board.pieces_not_to_move()) as i32)
     }
 
     fn merge(old: Self::V, new: Self::V) -> (Self::V, std::cmp::Ordering) {
@@ -88,20 +103,21 @@ impl Heuristic<Board> for MaterialHeuristic {
 }
 
 #[derive(Debug, Clone)]
-/// Returns an evaluation of how far the player's pawns are
+/// Returns an evaluation of how far the player's pawns are, negated in misère
 pub struct AdvancementHeuristic;
 
-impl Heuristic<Board> for AdvancementHeuristic {
+impl<B: PawnBoard> Heuristic<B> for AdvancementHeuristic {
     type V = i32;
 
-    fn value(&self, board: &Board, depth: u32) -> Self::V {
+    fn value(&self, board: &B, depth: u32) -> Self::V {
         // if the board is done, it's infinity for winning, negative infinity for losing
         if board.is_done() {
             return SolverHeuristicSimplified.value(board, depth);
         }
 
-        // return how far the pawns are
-        advancement_eval(board.pieces_to_move().0, board.side_to_move()) as i32
+        // return how far the pawns are, in misère pawns want to stay back
+        objective(board)
+            * advancement_eval(board.pieces_to_move(), board.side_to_move(), board.mask()) as i32
     }
 
     fn merge(old: Self::V, new: Self::V) -> (Self::V, std::cmp::Ordering) {
@@ -120,9 +136,11 @@ impl<R: Rng> Debug for AlwaysPushBot<R> {
     }
 }
 
-impl<R: Rng + Debug> Bot<Board> for AlwaysPushBot<R> {
-    fn select_move(&mut self, board: &Board) -> <Board as BoardTrait>::Move {
-        MoveGen::with_mask(board, Mask::Push)
+impl<R: Rng + Debug, B: PawnBoard> Bot<B> for AlwaysPushBot<R> {
+    fn select_move(&mut self, board: &B) -> B::Move {
+        board
+            .masked_moves(Mask::Push)
+            .into_iter()
             .choose(&mut self.rng)
             .unwrap_or_else(|| board.random_available_move(&mut self.rng))
     }
@@ -146,9 +164,11 @@ impl<R: Rng> Debug for AlwaysCaptureBot<R> {
     }
 }
 
-impl<R: Rng + Debug> Bot<Board> for AlwaysCaptureBot<R> {
-    fn select_move(&mut self, board: &Board) -> <Board as board_game::board::Board>::Move {
-        MoveGen::with_mask(board, Mask::Capture)
+impl<R: Rng + Debug, B: PawnBoard> Bot<B> for AlwaysCaptureBot<R> {
+    fn select_move(&mut self, board: &B) -> B::Move {
+        board
+            .masked_moves(Mask::Capture)
+            .into_iter()
             .choose(&mut self.rng)
             .unwrap_or_else(|| board.random_available_move(&mut self.rng))
     }
@@ -4,6 +4,7 @@
 use std::fmt::{Debug, Formatter};
 use std::marker::PhantomData;
 use std::num::NonZeroUsize;
+use std::time::Instant;
 
 use board_game::ai::mcts::{IdxRange, Node, SNodeKind, Tree};
 use board_game::ai::minimax::Heuristic;
@@ -16,6 +17,8 @@ use board_game::board::{Board, Outcome};
 use board_game::wdl::{Flip, OutcomeWDL, POV, WDL};
 use rand::prelude::IteratorRandom;
 
+use crate::clock::{Clock, TimeControl};
+
 fn new_node<M>(last_move: Option<M>, outcome: Option<OutcomeWDL>) -> Node<M> {
     let kind = match outcome {
         None => SNodeKind::Estimate(WDL::default()),
@@ -190,6 +193,7 @@ fn mcts_solver_step<B: Board>(
 fn mcts_build_tree<B: Board>(
     root_board: &B,
     iterations: u64,
+    deadline: Option<Instant>,
     exploration_weight: f32,
     heuristic: impl Heuristic<B, V = i32> + Clone,
     rng: &mut impl Rng,
@@ -203,11 +207,15 @@ fn mcts_build_tree<B: Board>(
         .map(|o| o.pov(root_board.next_player().other()));
     tree.nodes.push(new_node(None, root_outcome));
 
-    for _ in 0..iterations {
+    for i in 0..iterations {
         //we've solved the root node, so we're done
         if tree[0].solution().is_some() {
             break;
         }
+        // out of time, after at least one iteration so there's a move to play
+        if i > 0 && deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            break;
+        }
 
         mcts_solver_step(
             &mut tree,
@@ -223,7 +231,7 @@ fn mcts_build_tree<B: Board>(
 }
 
 /// A copy of [`board-game`](board-game)'s [`MCTSBot`](board-game::ai::mcts::MCTSBot)
-/// with progressive bias
+/// with progressive bias, limited by iterations and optionally by a [`Clock`](Clock)
 pub struct MCTSHeuristicBot<B: Board, H: Heuristic<B>, R: Rng> {
     /// How many iterations (MCTS playouts)
     iterations: u64,
@@ -233,22 +241,30 @@ pub struct MCTSHeuristicBot<B: Board, H: Heuristic<B>, R: Rng> {
     heuristic: H,
     /// Random number generation for random playouts
     rng: R,
+    /// The clock, if the iterations are also limited by time
+    clock: Option<Clock>,
     /// A marker for the type of board
     place_holder: PhantomData<B>,
 }
 
 impl<B: Board, H: Heuristic<B>, R: Rng> Debug for MCTSHeuristicBot<B, H, R> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // bots limited only by their clock have no limit on iterations
+        let iterations = match self.iterations {
+            u64::MAX => "unlimited".to_string(),
+            iterations => iterations.to_string(),
+        };
         write!(
             f,
             "MCTSHeuristicBot {{ iterations: {}, exploration_weight: {}, heuristic: {:?} }}",
-            self.iterations, self.exploration_weight, self.heuristic,
+            iterations, self.exploration_weight, self.heuristic,
         )
     }
 }
 
 impl<B: Board, H: Heuristic<B, V = i32> + Clone, R: Rng> MCTSHeuristicBot<B, H, R> {
-    /// Creates a new [`MCTSHeuristicbot`](MCTSHeuristicBot)
+    /// Creates a new [`MCTSHeuristicbot`](MCTSHeuristicBot).
+    /// With a clock, `u64::MAX` iterations iterates until the time for the move runs out
     pub fn new(iterations: u64, exploration_weight: f32, heuristic: H, rng: R) -> Self {
         assert!(iterations > 0);
         MCTSHeuristicBot {
@@ -256,15 +272,33 @@ impl<B: Board, H: Heuristic<B, V = i32> + Clone, R: Rng> MCTSHeuristicBot<B, H,
             exploration_weight,
             heuristic,
             rng,
+            clock: None,
             place_holder: PhantomData,
         }
     }
 
-    /// Creates a `MCTS`[`Tree`](Tree)
+    /// Stops the iterations when the time for the move runs out.
+    /// The [`Clock`](Clock) runs over every move, so the bot plays one game with it
+    pub fn with_time_control(mut self, time_control: TimeControl) -> Self {
+        self.clock = Some(Clock::new(time_control));
+        self
+    }
+
+    /// Returns the clock, if the iterations are also limited by time
+    pub fn clock(&self) -> Option<&Clock> {
+        self.clock.as_ref()
+    }
+
+    /// Creates a `MCTS`[`Tree`](Tree), within the time for the move if there's a clock
     pub fn build_tree(&mut self, board: &B) -> Tree<B> {
+        let deadline = self
+            .clock
+            .as_ref()
+            .map(|clock| clock.deadline(Instant::now()));
         mcts_build_tree(
             board,
             self.iterations,
+            deadline,
             self.exploration_weight,
             self.heuristic.clone(),
             &mut self.rng,
@@ -275,6 +309,11 @@ impl<B: Board, H: Heuristic<B, V = i32> + Clone, R: Rng> MCTSHeuristicBot<B, H,
 impl<R: Rng, B: Board, H: Heuristic<B, V = i32> + Clone> Bot<B> for MCTSHeuristicBot<B, H, R> {
     fn select_move(&mut self, board: &B) -> B::Move {
         assert!(!board.is_done());
-        self.build_tree(board).best_move()
+        let start = Instant::now();
+        let mv = self.build_tree(board).best_move();
+        if let Some(clock) = &mut self.clock {
+            clock.record(start.elapsed());
+        }
+        mv
     }
 }
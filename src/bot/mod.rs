@@ -1,4 +1,6 @@
 //! Implemented bots not in [`board-game`](board-game)
 
+pub mod alpha_beta_bot;
 pub mod heuristic;
 pub mod mcts_heuristic_bot;
+pub mod tablebase_bot;
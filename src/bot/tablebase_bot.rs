@@ -0,0 +1,55 @@
+//! The [`TablebaseBot`](TablebaseBot), which plays perfectly from a [`Tablebase`](Tablebase)
+
+use std::fmt::Debug;
+
+use board_game::{ai::Bot, board::Board as BoardTrait};
+use rand::{prelude::SliceRandom, Rng};
+
+use crate::{board::Board, move_gen::Move, tablebase::Tablebase};
+
+/// A bot that plays the fastest win, otherwise a draw, otherwise the slowest loss, from a [`Tablebase`](Tablebase).
+/// Positions the tablebase doesn't cover, like other sizes, are played by a fallback bot
+pub struct TablebaseBot<'a, F, R: Rng> {
+    /// The tablebase, if there is one for the size
+    tablebase: Option<&'a Tablebase>,
+    /// The bot for positions the tablebase doesn't cover
+    fallback: F,
+    /// Random number generation to choose between equally good moves
+    rng: R,
+}
+
+impl<'a, F: Debug, R: Rng> Debug for TablebaseBot<'a, F, R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TablebaseBot {{ fallback: {:?} }}", self.fallback)
+    }
+}
+
+impl<'a, F, R: Rng> TablebaseBot<'a, F, R> {
+    /// Creates a new [`TablebaseBot`](TablebaseBot)
+    pub fn new(tablebase: Option<&'a Tablebase>, fallback: F, rng: R) -> Self {
+        TablebaseBot {
+            tablebase,
+            fallback,
+            rng,
+        }
+    }
+}
+
+impl<'a, B, F, R> Bot<B> for TablebaseBot<'a, F, R>
+where
+    B: BoardTrait<Move = Move> + Into<Board>,
+    F: Bot<B>,
+    R: Rng,
+{
+    fn select_move(&mut self, board: &B) -> B::Move {
+        assert!(!board.is_done());
+        let best_moves = self
+            .tablebase
+            .and_then(|tablebase| tablebase.best_moves(&board.clone().into()));
+
+        match best_moves {
+            Some(moves) => *moves.choose(&mut self.rng).unwrap(),
+            None => self.fallback.select_move(board),
+        }
+    }
+}
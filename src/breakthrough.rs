@@ -0,0 +1,287 @@
+//! Board representation of [breakthrough](https://en.wikipedia.org/wiki/Breakthrough_(board_game)),
+//! hexapawn's bigger sibling
+//!
+//! Each side starts with two rows of pawns. Pawns move one square forward, straight or diagonally,
+//! and capture diagonally. Straight moves can't capture.
+//! The first side to reach the far rank or to capture every opposing pawn wins
+
+use std::fmt::Display;
+use std::hash::Hash;
+
+use board_game::board::Board as BoardTrait;
+use board_game::board::BoardMoves;
+use board_game::board::BoardSymmetry;
+use board_game::board::Outcome;
+use board_game::board::Player;
+use board_game::symmetry::D1Symmetry;
+use chess::{BitBoard, Color};
+use internal_iterator::Internal;
+use internal_iterator::IteratorExt;
+
+use crate::bitboard::BitBoardBackend;
+use crate::board::AllMoves;
+use crate::move_gen::{Mask, Move, MoveError, MoveGen, PawnTargets};
+use crate::pawn_board::{fmt_pawns, PawnBoard};
+
+/// A representation of breakthrough of various sizes, square or rectangular
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Board<BB: BitBoardBackend = BitBoard> {
+    /// White's pawns
+    white: BB,
+    /// Black's pawns
+    black: BB,
+    /// The side to move
+    side_to_move: Color,
+    /// The width of the board, from 3 to `BB::MAX_SIZE`
+    width: usize,
+    /// The height of the board, from 4 to `BB::MAX_SIZE`
+    height: usize,
+}
+
+impl<BB: BitBoardBackend> Display for Board<BB> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt_pawns(f, self.white, self.black, self.width, self.height)
+    }
+}
+
+impl Board {
+    /// Creates a new square `Board`.
+    /// The size must be 4 to 8
+    pub fn new(size: usize) -> Self {
+        Board::with_dimensions(size, size)
+    }
+
+    /// Creates a new `Board` with a width and height.
+    /// The width must be 3 to 8 and the height 4 to 8
+    pub fn with_dimensions(width: usize, height: usize) -> Self {
+        Board::starting_position(width, height)
+    }
+}
+
+impl<BB: BitBoardBackend> Board<BB> {
+    /// Creates a new `Board` with a width and height on any bitboard backend.
+    /// The width must be 3 to `BB::MAX_SIZE` and the height 4 to `BB::MAX_SIZE`, for two rows of pawns on each side
+    pub fn starting_position(width: usize, height: usize) -> Self {
+        assert!(
+            (3..=BB::MAX_SIZE).contains(&width),
+            "Invalid width, must be 3 to {}",
+            BB::MAX_SIZE
+        );
+        assert!(
+            (4..=BB::MAX_SIZE).contains(&height),
+            "Invalid height, must be 4 to {}",
+            BB::MAX_SIZE
+        );
+        // white starts on ranks 1 and 2
+        let start = BB::board_mask(width, 2);
+        Board {
+            white: start,
+            // black starts on the top two ranks
+            black: start.shift_up(BB::STRIDE * (height - 2)),
+            side_to_move: Color::White,
+            width,
+            height,
+        }
+    }
+
+    /// Returns the bitboard of a certain color's pawns
+    pub fn pieces(&self, color: Color) -> BB {
+        match color {
+            Color::White => self.white,
+            Color::Black => self.black,
+        }
+    }
+
+    /// Returns a mutable reference to a certain color's bitboard
+    fn pieces_mut(&mut self, color: Color) -> &mut BB {
+        match color {
+            Color::White => &mut self.white,
+            Color::Black => &mut self.black,
+        }
+    }
+
+    /// Returns the side to move, White or Black
+    pub fn side_to_move(&self) -> Color {
+        self.side_to_move
+    }
+
+    /// Returns a bitboard of all occupied squares
+    pub fn occupied(&self) -> BB {
+        self.white | self.black
+    }
+
+    /// Returns the width of the board
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of the board
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns a bitboard of the playing area
+    pub fn mask(&self) -> BB {
+        BB::board_mask(self.width, self.height)
+    }
+
+    /// Returns the rank a color has to reach to win
+    fn win_rank(&self, color: Color) -> BB {
+        match color {
+            Color::White => BB::rank(self.height - 1),
+            Color::Black => BB::rank(0),
+        }
+    }
+
+    /// Generates the moves of the side to move with a move generation mask
+    pub fn move_gen(&self, mask: Mask) -> MoveGen<BB> {
+        let color = self.side_to_move;
+        let opponent = self.pieces(!color);
+        let empty = !self.occupied() & self.mask();
+
+        let (straight, diagonal) = match mask {
+            // straight moves to empty squares, diagonal moves to empty squares or captures
+            Mask::None => (empty, empty | opponent),
+            // only diagonal captures
+            Mask::Capture => (BB::EMPTY, opponent),
+            // only moves to empty squares
+            Mask::Push => (empty, empty),
+        };
+        let targets = PawnTargets::generate(
+            self.pieces(color),
+            color,
+            self.width,
+            straight,
+            diagonal,
+            None,
+        );
+        MoveGen::from_targets(color, targets)
+    }
+
+    /// Parses a move like `a1a2` and checks that it's legal in this position
+    pub fn parse_move(&self, s: &str) -> Result<Move<BB>, MoveError<BB>> {
+        let mv: Move<BB> = s.parse()?;
+
+        for sq in [mv.src(), mv.dest()] {
+            if (BB::from_index(BB::index(sq)) & self.mask()).is_empty() {
+                return Err(MoveError::OffBoard(sq));
+            }
+        }
+
+        if self.move_gen(Mask::None).any(|x| x == mv) {
+            Ok(mv)
+        } else {
+            Err(MoveError::Illegal(mv))
+        }
+    }
+}
+
+impl<BB: BitBoardBackend> BoardSymmetry<Board<BB>> for Board<BB> {
+    type Symmetry = D1Symmetry;
+
+    fn map(&self, sym: Self::Symmetry) -> Self {
+        if !sym.mirror {
+            return self.clone();
+        }
+
+        Board {
+            white: self.white.mirror(self.width) & self.mask(),
+            black: self.black.mirror(self.width) & self.mask(),
+            ..self.clone()
+        }
+    }
+
+    fn map_move(&self, sym: Self::Symmetry, mv: Move<BB>) -> Move<BB> {
+        if !sym.mirror {
+            return mv;
+        }
+
+        // mirrors a square across the vertical axis of the board
+        let mirror = |index: usize| {
+            let (file, rank) = (index % BB::STRIDE, index / BB::STRIDE);
+            BB::square(rank * BB::STRIDE + self.width - 1 - file)
+        };
+        Move::from_squares(mirror(mv.src_index()), mirror(mv.dest_index()))
+    }
+}
+
+impl<BB: BitBoardBackend> BoardTrait for Board<BB> {
+    type Move = Move<BB>;
+
+    fn next_player(&self) -> Player {
+        match self.side_to_move {
+            Color::White => Player::A,
+            Color::Black => Player::B,
+        }
+    }
+
+    fn is_available_move(&self, mv: Self::Move) -> bool {
+        self.move_gen(Mask::None).any(|x| x == mv)
+    }
+
+    fn play(&mut self, mv: Self::Move) {
+        let src_bb = BB::from_index(mv.src_index());
+        let dest_bb = BB::from_index(mv.dest_index());
+
+        *self.pieces_mut(self.side_to_move) ^= src_bb | dest_bb; // Move the pawn
+        *self.pieces_mut(!self.side_to_move) &= !dest_bb; // Remove the captured pawn
+        self.side_to_move = !self.side_to_move; // Switch sides to move
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        // a side wins by reaching the far rank or by capturing every opposing pawn
+        if !(self.white & self.win_rank(Color::White)).is_empty() || self.black.is_empty() {
+            Some(Outcome::WonBy(Player::A))
+        } else if !(self.black & self.win_rank(Color::Black)).is_empty() || self.white.is_empty() {
+            Some(Outcome::WonBy(Player::B))
+        // if there are no moves, it's a draw
+        } else if !self.move_gen(Mask::None).has_any_move() {
+            Some(Outcome::Draw)
+        // otherwise, the game is still ongoing
+        } else {
+            None
+        }
+    }
+
+    fn can_lose_after_move() -> bool {
+        false
+    }
+}
+
+impl<BB: BitBoardBackend> PawnBoard for Board<BB> {
+    type BB = BB;
+
+    fn pieces(&self, color: Color) -> BB {
+        Board::pieces(self, color)
+    }
+
+    fn side_to_move(&self) -> Color {
+        self.side_to_move
+    }
+
+    fn mask(&self) -> BB {
+        Board::mask(self)
+    }
+
+    fn move_gen(&self, mask: Mask) -> MoveGen<BB> {
+        Board::move_gen(self, mask)
+    }
+
+    fn win_rank(&self, color: Color) -> BB {
+        Board::win_rank(self, color)
+    }
+}
+
+impl<'a, BB: BitBoardBackend> BoardMoves<'a, Board<BB>> for Board<BB> {
+    type AllMovesIterator = AllMoves<BB>;
+
+    type AvailableMovesIterator = Internal<MoveGen<BB>>;
+
+    fn all_possible_moves() -> Self::AllMovesIterator {
+        AllMoves::default()
+    }
+
+    fn available_moves(&'a self) -> Self::AvailableMovesIterator {
+        self.move_gen(Mask::None).into_internal()
+    }
+}
@@ -0,0 +1,86 @@
+//! Time controls, so bots can search under a clock instead of for a fixed amount of work
+//!
+//! A bot with a [`Clock`](Clock) asks it for the [budget](Clock::budget) of each move,
+//! stops searching once the budget is spent, then [records](Clock::record) how long the move took
+
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+/// How much time a bot gets to think
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeControl {
+    /// The same time for every move
+    PerMove(Duration),
+    /// A time for the whole game, plus an increment after every move
+    PerGame { time: Duration, increment: Duration },
+}
+
+impl Display for TimeControl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeControl::PerMove(time) => write!(f, "{time:?} per move"),
+            TimeControl::PerGame { time, increment } => {
+                write!(f, "{time:?} per game + {increment:?} per move")
+            }
+        }
+    }
+}
+
+/// A bot's clock for one game
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clock {
+    /// The time control
+    time_control: TimeControl,
+    /// The time left for the game, which is unused with [`PerMove`](TimeControl::PerMove)
+    remaining: Duration,
+}
+
+impl Clock {
+    /// The amount of moves the time left for the game is spread over
+    const MOVES_TO_GO: u32 = 16;
+
+    /// Creates a new `Clock` at the start of a game
+    pub fn new(time_control: TimeControl) -> Self {
+        let remaining = match time_control {
+            TimeControl::PerMove(_) => Duration::ZERO,
+            TimeControl::PerGame { time, .. } => time,
+        };
+        Clock {
+            time_control,
+            remaining,
+        }
+    }
+
+    /// Returns the time control
+    pub fn time_control(&self) -> TimeControl {
+        self.time_control
+    }
+
+    /// Returns the time left for the game
+    pub fn remaining(&self) -> Duration {
+        self.remaining
+    }
+
+    /// Returns how long the next move may take. With a time for the whole game,
+    /// it's a share of the time left plus the increment, but never more than the time left
+    pub fn budget(&self) -> Duration {
+        match self.time_control {
+            TimeControl::PerMove(time) => time,
+            TimeControl::PerGame { increment, .. } => {
+                (self.remaining / Self::MOVES_TO_GO + increment).min(self.remaining)
+            }
+        }
+    }
+
+    /// Returns when a move started at `start` has to be played
+    pub fn deadline(&self, start: Instant) -> Instant {
+        start + self.budget()
+    }
+
+    /// Records a move that took `elapsed`, adding the increment
+    pub fn record(&mut self, elapsed: Duration) {
+        if let TimeControl::PerGame { increment, .. } = self.time_control {
+            self.remaining = self.remaining.saturating_sub(elapsed) + increment;
+        }
+    }
+}
@@ -47,6 +47,18 @@ pub const BOARD_MASKS: [u64; 8] = [
     0xFFFFFFFFFFFFFFFF,
 ];
 
+/// Returns the playing area for a board with a width and height from 1 to 8.
+/// [`board_mask`](board_mask)`(size, size)` is [`BOARD_MASKS`](BOARD_MASKS)`[size - 1]`
+pub const fn board_mask(width: usize, height: usize) -> u64 {
+    let files = ((1 << width) - 1) * FILES[0];
+    let ranks = if height == 8 {
+        UNIVERSAL
+    } else {
+        (1 << (8 * height)) - 1
+    };
+    files & ranks
+}
+
 /// The starting positions for white.
 /// [`START_POS_WHITE`](START_POS_WHITE)`\[0\]` and [`START_POS_WHITE`](START_POS_WHITE)`\[1\]` are padding
 pub const START_POS_WHITE: [u64; 8] = [
@@ -72,3 +84,31 @@ pub const START_POS_BLACK: [u64; 8] = [
     0x007F000000000000,
     0xFF00000000000000,
 ];
+
+/// [SplitMix64](https://prng.di.unimi.it/splitmix64.c), used to generate the Zobrist keys at compile time
+const fn splitmix64(state: u64) -> u64 {
+    let mut z = state.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+/// Generates a Zobrist key for every square of the largest bitboard from a seed
+const fn zobrist_keys(seed: u64) -> [u64; 128] {
+    let mut keys = [0; 128];
+    let mut i = 0;
+    while i < 128 {
+        keys[i] = splitmix64(seed * 128 + i as u64);
+        i += 1;
+    }
+    keys
+}
+
+/// [Zobrist keys](https://www.chessprogramming.org/Zobrist_Hashing) for a white pawn on each square
+pub const ZOBRIST_WHITE: [u64; 128] = zobrist_keys(1);
+/// [Zobrist keys](https://www.chessprogramming.org/Zobrist_Hashing) for a black pawn on each square
+pub const ZOBRIST_BLACK: [u64; 128] = zobrist_keys(2);
+/// [Zobrist keys](https://www.chessprogramming.org/Zobrist_Hashing) for each square that can be captured en passant
+pub const ZOBRIST_EN_PASSANT: [u64; 128] = zobrist_keys(3);
+/// The [Zobrist key](https://www.chessprogramming.org/Zobrist_Hashing) for black to move
+pub const ZOBRIST_BLACK_TO_MOVE: u64 = splitmix64(0);
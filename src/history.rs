@@ -0,0 +1,75 @@
+//! A game history on top of [`Board`](Board) with takebacks
+
+use chess::BitBoard;
+
+use crate::bitboard::BitBoardBackend;
+use crate::board::{Board, Undo};
+use crate::move_gen::Move;
+
+/// A [`Board`](Board) and the moves played on it, which can be taken back
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameHistory<BB: BitBoardBackend = BitBoard> {
+    /// The current position
+    board: Board<BB>,
+    /// The moves played, and how to undo them
+    moves: Vec<(Move<BB>, Undo)>,
+}
+
+impl<BB: BitBoardBackend> GameHistory<BB> {
+    /// Creates a new `GameHistory` starting from `start`
+    pub fn new(start: Board<BB>) -> Self {
+        GameHistory {
+            board: start,
+            moves: vec![],
+        }
+    }
+
+    /// Returns the current position
+    pub fn board(&self) -> &Board<BB> {
+        &self.board
+    }
+
+    /// Returns the starting position
+    pub fn start(&self) -> Board<BB> {
+        let mut board = self.board.clone();
+        for &(mv, undo) in self.moves.iter().rev() {
+            board.unmake(mv, undo);
+        }
+        board
+    }
+
+    /// Returns the moves played, from first to last
+    pub fn moves(&self) -> impl Iterator<Item = Move<BB>> + '_ {
+        self.moves.iter().map(|&(mv, _)| mv)
+    }
+
+    /// Returns the amount of moves played
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// Returns whether no moves have been played
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Plays a move
+    pub fn play(&mut self, mv: Move<BB>) {
+        let undo = self.board.make(mv);
+        self.moves.push((mv, undo));
+    }
+
+    /// Takes back the last move, returning it. Returns `None` if no moves have been played
+    pub fn takeback(&mut self) -> Option<Move<BB>> {
+        let (mv, undo) = self.moves.pop()?;
+        self.board.unmake(mv, undo);
+        Some(mv)
+    }
+
+    /// Takes back moves until only `len` moves are left
+    pub fn takeback_to(&mut self, len: usize) {
+        while self.moves.len() > len {
+            self.takeback();
+        }
+    }
+}
@@ -1,10 +1,23 @@
+pub mod bitboard;
 pub mod board;
 pub mod bot;
+pub mod breakthrough;
+pub mod clock;
 pub mod consts;
+pub mod history;
 pub mod move_gen;
+pub mod move_order;
+pub mod pawn_board;
+pub mod perft;
+pub mod record;
+pub mod rules;
+pub mod sized_board;
+pub mod solver;
+pub mod tablebase;
 
 // pub const SIZES: [usize; 2] = [3, 4];
-/// All possible sizes of [`Board`](board::Board), from 3 to 8
+/// All possible sizes of [`Board`](board::Board), from 3 to 8.
+/// [`with_size!`](with_size) turns one into the size of a [`SizedBoard`](sized_board::SizedBoard)
 pub const SIZES: [usize; 6] = [3, 4, 5, 6, 7, 8];
 
 use std::path::PathBuf;
@@ -14,5 +27,22 @@ pub fn output_path() -> PathBuf {
     dirs::home_dir().unwrap().join("sf21_22_output")
 }
 
+/// Returns the output file path for the results of the clocked tournament
+pub fn clocked_output_path() -> PathBuf {
+    dirs::home_dir().unwrap().join("sf21_22_clocked_output")
+}
+
+/// Returns the file path for the archived game records
+pub fn games_path() -> PathBuf {
+    dirs::home_dir().unwrap().join("sf21_22_games")
+}
+
+/// Returns the directory path for the [tablebase](tablebase::Tablebase) files
+pub fn tablebases_path() -> PathBuf {
+    dirs::home_dir().unwrap().join("sf21_22_tablebases")
+}
+
+#[cfg(test)]
+mod reference;
 #[cfg(test)]
 mod tests;
@@ -1,25 +1,37 @@
 use std::{
     fmt::Debug,
-    fs::File,
-    io::{BufWriter, Write},
+    fs::{self, File},
+    io::{BufWriter, ErrorKind, Write},
+    time::Duration,
 };
 
 use board_game::{
     ai::{mcts::MCTSBot, minimax::MiniMaxBot, simple::RandomBot},
-    util::bot_game::{run, BotGameResult},
+    board::Board as BoardTrait,
+    util::bot_game::{BotGameResult, Replay},
     wdl::WDL,
 };
 use rand::thread_rng;
+use rayon::ThreadPoolBuilder;
 use sf21_22::{
     board::Board,
     bot::{
+        alpha_beta_bot::AlphaBetaBot,
         heuristic::{
             AdvancementHeuristic, AlwaysCaptureBot, AlwaysPushBot, MaterialHeuristic,
             SolverHeuristicSimplified,
         },
         mcts_heuristic_bot::MCTSHeuristicBot,
+        tablebase_bot::TablebaseBot,
     },
-    output_path, SIZES,
+    breakthrough,
+    clock::TimeControl,
+    clocked_output_path, games_path, output_path,
+    record::{run_timed, GameRecord},
+    rules::{Rules, StalemateRule},
+    sized_board::SizedBoard,
+    tablebase::{Tablebase, TablebaseError},
+    tablebases_path, with_size, SIZES,
 };
 use time::OffsetDateTime;
 
@@ -27,16 +39,47 @@ fn main() {
     const MIN_MAX_DEPTH: u32 = 10;
     const MCTS_ITERATIONS: u64 = 10_000;
     const MCTS_EXPLORATION: f32 = 2.0;
+    // clocked MCTSHeuristicBots iterate until their time runs out
+    const MCTS_CLOCKED_ITERATIONS: u64 = u64::MAX;
+    // the deepest an AlphaBetaBot searches, its clock stops it first
+    const ALPHA_BETA_MAX_DEPTH: u32 = 64;
+    // the biggest size a tablebase is generated for
+    const TABLEBASE_MAX_SIZE: usize = 5;
+    // whether the bots that can search under a clock also play each other with one.
+    // MiniMaxBot and MCTSBot from board-game can't be stopped by a clock, so they don't play
+    const CLOCKED: bool = false;
+    // the clock of every bot in the clocked tournament, so they get the same time to think
+    const TIME_CONTROL: TimeControl = TimeControl::PerGame {
+        time: Duration::from_secs(1),
+        increment: Duration::from_millis(10),
+    };
 
     const TRIALS_PER: u32 = 1000;
     const GAMES_PER_SIDE: u32 = TRIALS_PER / 4;
     const BOTH_SIDES: bool = true;
 
-    let file = File::create(output_path()).unwrap();
-    let mut buf = BufWriter::new(file);
+    // the rules hexapawn is played with, written at the top of the results
+    const RULES: Rules = Rules {
+        stalemate: StalemateRule::Draw,
+        ..Rules::STANDARD
+    };
+
+    let mut buf = Output {
+        results: BufWriter::new(File::create(output_path()).unwrap()),
+        games: BufWriter::new(File::create(games_path()).unwrap()),
+    };
+    buf.results
+        .write_fmt(format_args!("rules: {RULES}\n"))
+        .unwrap();
     for size in SIZES {
         println!("size: {size}");
-        buf.write_fmt(format_args!("\n\nsize: {size}\n\n")).unwrap();
+        buf.results
+            .write_fmt(format_args!("\n\nsize: {size}\n\n"))
+            .unwrap();
+
+        // the tablebase for the size, read from its file or generated and saved
+        let tablebase = (size <= TABLEBASE_MAX_SIZE).then(|| load_tablebase(size, RULES));
+        let tablebase = tablebase.as_ref();
 
         println!("Running (at {}): Random", OffsetDateTime::now_utc());
         r!(buf, size, || RandomBot::new(thread_rng()), || {
@@ -878,25 +921,645 @@ fn main() {
                 thread_rng()
             )
         );
+
+        println!("Running (at {}): Tablebase", OffsetDateTime::now_utc());
+        r!(
+            buf,
+            size,
+            || TablebaseBot::new(
+                tablebase,
+                MiniMaxBot::new(MIN_MAX_DEPTH, SolverHeuristicSimplified, thread_rng()),
+                thread_rng()
+            ),
+            || RandomBot::new(thread_rng())
+        );
+        r!(
+            buf,
+            size,
+            || TablebaseBot::new(
+                tablebase,
+                MiniMaxBot::new(MIN_MAX_DEPTH, SolverHeuristicSimplified, thread_rng()),
+                thread_rng()
+            ),
+            || AlwaysPushBot::new(thread_rng())
+        );
+        r!(
+            buf,
+            size,
+            || TablebaseBot::new(
+                tablebase,
+                MiniMaxBot::new(MIN_MAX_DEPTH, SolverHeuristicSimplified, thread_rng()),
+                thread_rng()
+            ),
+            || AlwaysCaptureBot::new(thread_rng())
+        );
+        r!(
+            buf,
+            size,
+            || TablebaseBot::new(
+                tablebase,
+                MiniMaxBot::new(MIN_MAX_DEPTH, SolverHeuristicSimplified, thread_rng()),
+                thread_rng()
+            ),
+            || MiniMaxBot::new(MIN_MAX_DEPTH, SolverHeuristicSimplified, thread_rng())
+        );
+        r!(
+            buf,
+            size,
+            || TablebaseBot::new(
+                tablebase,
+                MiniMaxBot::new(MIN_MAX_DEPTH, SolverHeuristicSimplified, thread_rng()),
+                thread_rng()
+            ),
+            || MiniMaxBot::new(MIN_MAX_DEPTH, MaterialHeuristic, thread_rng())
+        );
+        r!(
+            buf,
+            size,
+            || TablebaseBot::new(
+                tablebase,
+                MiniMaxBot::new(MIN_MAX_DEPTH, SolverHeuristicSimplified, thread_rng()),
+                thread_rng()
+            ),
+            || MiniMaxBot::new(MIN_MAX_DEPTH, AdvancementHeuristic, thread_rng())
+        );
+        r!(
+            buf,
+            size,
+            || TablebaseBot::new(
+                tablebase,
+                MiniMaxBot::new(MIN_MAX_DEPTH, SolverHeuristicSimplified, thread_rng()),
+                thread_rng()
+            ),
+            || MCTSBot::new(MCTS_ITERATIONS, MCTS_EXPLORATION, thread_rng())
+        );
+        r!(
+            buf,
+            size,
+            || TablebaseBot::new(
+                tablebase,
+                MiniMaxBot::new(MIN_MAX_DEPTH, SolverHeuristicSimplified, thread_rng()),
+                thread_rng()
+            ),
+            || MCTSHeuristicBot::new(
+                MCTS_ITERATIONS,
+                MCTS_EXPLORATION,
+                SolverHeuristicSimplified,
+                thread_rng()
+            )
+        );
+        r!(
+            buf,
+            size,
+            || TablebaseBot::new(
+                tablebase,
+                MiniMaxBot::new(MIN_MAX_DEPTH, SolverHeuristicSimplified, thread_rng()),
+                thread_rng()
+            ),
+            || MCTSHeuristicBot::new(
+                MCTS_ITERATIONS,
+                MCTS_EXPLORATION,
+                MaterialHeuristic,
+                thread_rng()
+            )
+        );
+        r!(
+            buf,
+            size,
+            || TablebaseBot::new(
+                tablebase,
+                MiniMaxBot::new(MIN_MAX_DEPTH, SolverHeuristicSimplified, thread_rng()),
+                thread_rng()
+            ),
+            || MCTSHeuristicBot::new(
+                MCTS_ITERATIONS,
+                MCTS_EXPLORATION,
+                AdvancementHeuristic,
+                thread_rng()
+            )
+        );
+        r!(
+            buf,
+            size,
+            || TablebaseBot::new(
+                tablebase,
+                MiniMaxBot::new(MIN_MAX_DEPTH, SolverHeuristicSimplified, thread_rng()),
+                thread_rng()
+            ),
+            || TablebaseBot::new(
+                tablebase,
+                MiniMaxBot::new(MIN_MAX_DEPTH, SolverHeuristicSimplified, thread_rng()),
+                thread_rng()
+            )
+        );
+    }
+    buf.results.flush().unwrap();
+
+    if CLOCKED {
+        // the clocked bots play each other in their own results, one game at a time,
+        // so every bot gets the whole of its clock instead of sharing the cores with other games
+        buf.results = BufWriter::new(File::create(clocked_output_path()).unwrap());
+        buf.results
+            .write_fmt(format_args!("rules: {RULES}\n"))
+            .unwrap();
+        buf.results
+            .write_fmt(format_args!("time control: {TIME_CONTROL}\n"))
+            .unwrap();
+        let pool = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        pool.install(|| {
+            for size in SIZES {
+                println!("clocked size: {size}");
+                buf.results
+                    .write_fmt(format_args!("\n\nsize: {size}\n\n"))
+                    .unwrap();
+
+                let tablebase = (size <= TABLEBASE_MAX_SIZE).then(|| load_tablebase(size, RULES));
+                let tablebase = tablebase.as_ref();
+
+                println!("Running (at {}): MCTSSolver", OffsetDateTime::now_utc());
+                r!(
+                    buf,
+                    size,
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        SolverHeuristicSimplified,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL),
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        SolverHeuristicSimplified,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL)
+                );
+                r!(
+                    buf,
+                    size,
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        SolverHeuristicSimplified,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL),
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        MaterialHeuristic,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL)
+                );
+                r!(
+                    buf,
+                    size,
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        SolverHeuristicSimplified,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL),
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        AdvancementHeuristic,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL)
+                );
+                r!(
+                    buf,
+                    size,
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        SolverHeuristicSimplified,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL),
+                    || AlphaBetaBot::new(ALPHA_BETA_MAX_DEPTH, MaterialHeuristic)
+                        .with_time_control(TIME_CONTROL)
+                );
+                r!(
+                    buf,
+                    size,
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        SolverHeuristicSimplified,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL),
+                    || TablebaseBot::new(
+                        tablebase,
+                        AlphaBetaBot::new(ALPHA_BETA_MAX_DEPTH, MaterialHeuristic)
+                            .with_time_control(TIME_CONTROL),
+                        thread_rng()
+                    )
+                );
+
+                println!("Running (at {}): MCTSMaterial", OffsetDateTime::now_utc());
+                r!(
+                    buf,
+                    size,
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        MaterialHeuristic,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL),
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        SolverHeuristicSimplified,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL)
+                );
+                r!(
+                    buf,
+                    size,
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        MaterialHeuristic,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL),
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        MaterialHeuristic,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL)
+                );
+                r!(
+                    buf,
+                    size,
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        MaterialHeuristic,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL),
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        AdvancementHeuristic,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL)
+                );
+                r!(
+                    buf,
+                    size,
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        MaterialHeuristic,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL),
+                    || AlphaBetaBot::new(ALPHA_BETA_MAX_DEPTH, MaterialHeuristic)
+                        .with_time_control(TIME_CONTROL)
+                );
+                r!(
+                    buf,
+                    size,
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        MaterialHeuristic,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL),
+                    || TablebaseBot::new(
+                        tablebase,
+                        AlphaBetaBot::new(ALPHA_BETA_MAX_DEPTH, MaterialHeuristic)
+                            .with_time_control(TIME_CONTROL),
+                        thread_rng()
+                    )
+                );
+
+                println!(
+                    "Running (at {}): MCTSAdvancement",
+                    OffsetDateTime::now_utc()
+                );
+                r!(
+                    buf,
+                    size,
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        AdvancementHeuristic,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL),
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        SolverHeuristicSimplified,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL)
+                );
+                r!(
+                    buf,
+                    size,
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        AdvancementHeuristic,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL),
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        MaterialHeuristic,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL)
+                );
+                r!(
+                    buf,
+                    size,
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        AdvancementHeuristic,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL),
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        AdvancementHeuristic,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL)
+                );
+                r!(
+                    buf,
+                    size,
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        AdvancementHeuristic,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL),
+                    || AlphaBetaBot::new(ALPHA_BETA_MAX_DEPTH, MaterialHeuristic)
+                        .with_time_control(TIME_CONTROL)
+                );
+                r!(
+                    buf,
+                    size,
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        AdvancementHeuristic,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL),
+                    || TablebaseBot::new(
+                        tablebase,
+                        AlphaBetaBot::new(ALPHA_BETA_MAX_DEPTH, MaterialHeuristic)
+                            .with_time_control(TIME_CONTROL),
+                        thread_rng()
+                    )
+                );
+
+                println!("Running (at {}): AlphaBeta", OffsetDateTime::now_utc());
+                r!(
+                    buf,
+                    size,
+                    || AlphaBetaBot::new(ALPHA_BETA_MAX_DEPTH, MaterialHeuristic)
+                        .with_time_control(TIME_CONTROL),
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        SolverHeuristicSimplified,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL)
+                );
+                r!(
+                    buf,
+                    size,
+                    || AlphaBetaBot::new(ALPHA_BETA_MAX_DEPTH, MaterialHeuristic)
+                        .with_time_control(TIME_CONTROL),
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        MaterialHeuristic,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL)
+                );
+                r!(
+                    buf,
+                    size,
+                    || AlphaBetaBot::new(ALPHA_BETA_MAX_DEPTH, MaterialHeuristic)
+                        .with_time_control(TIME_CONTROL),
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        AdvancementHeuristic,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL)
+                );
+                r!(
+                    buf,
+                    size,
+                    || AlphaBetaBot::new(ALPHA_BETA_MAX_DEPTH, MaterialHeuristic)
+                        .with_time_control(TIME_CONTROL),
+                    || AlphaBetaBot::new(ALPHA_BETA_MAX_DEPTH, MaterialHeuristic)
+                        .with_time_control(TIME_CONTROL)
+                );
+                r!(
+                    buf,
+                    size,
+                    || AlphaBetaBot::new(ALPHA_BETA_MAX_DEPTH, MaterialHeuristic)
+                        .with_time_control(TIME_CONTROL),
+                    || TablebaseBot::new(
+                        tablebase,
+                        AlphaBetaBot::new(ALPHA_BETA_MAX_DEPTH, MaterialHeuristic)
+                            .with_time_control(TIME_CONTROL),
+                        thread_rng()
+                    )
+                );
+
+                println!("Running (at {}): Tablebase", OffsetDateTime::now_utc());
+                r!(
+                    buf,
+                    size,
+                    || TablebaseBot::new(
+                        tablebase,
+                        AlphaBetaBot::new(ALPHA_BETA_MAX_DEPTH, MaterialHeuristic)
+                            .with_time_control(TIME_CONTROL),
+                        thread_rng()
+                    ),
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        SolverHeuristicSimplified,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL)
+                );
+                r!(
+                    buf,
+                    size,
+                    || TablebaseBot::new(
+                        tablebase,
+                        AlphaBetaBot::new(ALPHA_BETA_MAX_DEPTH, MaterialHeuristic)
+                            .with_time_control(TIME_CONTROL),
+                        thread_rng()
+                    ),
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        MaterialHeuristic,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL)
+                );
+                r!(
+                    buf,
+                    size,
+                    || TablebaseBot::new(
+                        tablebase,
+                        AlphaBetaBot::new(ALPHA_BETA_MAX_DEPTH, MaterialHeuristic)
+                            .with_time_control(TIME_CONTROL),
+                        thread_rng()
+                    ),
+                    || MCTSHeuristicBot::new(
+                        MCTS_CLOCKED_ITERATIONS,
+                        MCTS_EXPLORATION,
+                        AdvancementHeuristic,
+                        thread_rng()
+                    )
+                    .with_time_control(TIME_CONTROL)
+                );
+                r!(
+                    buf,
+                    size,
+                    || TablebaseBot::new(
+                        tablebase,
+                        AlphaBetaBot::new(ALPHA_BETA_MAX_DEPTH, MaterialHeuristic)
+                            .with_time_control(TIME_CONTROL),
+                        thread_rng()
+                    ),
+                    || AlphaBetaBot::new(ALPHA_BETA_MAX_DEPTH, MaterialHeuristic)
+                        .with_time_control(TIME_CONTROL)
+                );
+                r!(
+                    buf,
+                    size,
+                    || TablebaseBot::new(
+                        tablebase,
+                        AlphaBetaBot::new(ALPHA_BETA_MAX_DEPTH, MaterialHeuristic)
+                            .with_time_control(TIME_CONTROL),
+                        thread_rng()
+                    ),
+                    || TablebaseBot::new(
+                        tablebase,
+                        AlphaBetaBot::new(ALPHA_BETA_MAX_DEPTH, MaterialHeuristic)
+                            .with_time_control(TIME_CONTROL),
+                        thread_rng()
+                    )
+                );
+            }
+        });
+        buf.results.flush().unwrap();
+    }
+    buf.games.flush().unwrap()
+}
+
+/// Reads the tablebase of a size from its file. If there's no file yet, it's generated and saved.
+/// Panics if the file can't be read, so a corrupt file isn't silently regenerated on every run
+fn load_tablebase(size: usize, rules: Rules) -> Tablebase {
+    let path = Tablebase::path(size, size, rules);
+    match Tablebase::load(&path) {
+        Ok(tablebase) => tablebase,
+        Err(TablebaseError::Io(err)) if err.kind() == ErrorKind::NotFound => {
+            println!("Generating the tablebase for {size}x{size}");
+            let tablebase =
+                Tablebase::generate_reachable(&Board::new(size).with_rules(rules)).unwrap();
+            fs::create_dir_all(tablebases_path()).unwrap();
+            tablebase.save(&path).unwrap();
+            tablebase
+        }
+        Err(err) => panic!(
+            "Couldn't read the tablebase {}, delete it to generate it again: {err}",
+            path.display()
+        ),
+    }
+}
+
+/// The files results and game records are written to
+pub struct Output {
+    results: BufWriter<File>,
+    games: BufWriter<File>,
+}
+
+/// Boards whose games can be archived
+pub trait Archive: BoardTrait {
+    /// Returns the text of a game with the time each move took to archive,
+    /// or `None` if the game can't be recorded
+    fn archive(replay: &Replay<Self>, times: &[f32]) -> Option<String>;
+}
+
+impl Archive for Board {
+    fn archive(replay: &Replay<Self>, times: &[f32]) -> Option<String> {
+        Some(GameRecord::from(replay).with_times(times).to_string())
+    }
+}
+
+impl<const N: usize> Archive for SizedBoard<N> {
+    fn archive(replay: &Replay<Self>, times: &[f32]) -> Option<String> {
+        Some(GameRecord::from(replay).with_times(times).to_string())
+    }
+}
+
+impl Archive for breakthrough::Board {
+    fn archive(_: &Replay<Self>, _: &[f32]) -> Option<String> {
+        // game records only describe hexapawn
+        None
     }
-    buf.flush().unwrap()
 }
 
 #[macro_export]
 macro_rules! r {
+    // runs the bots on any board, like `start: || breakthrough::Board::new(size)`
+    ($buf:expr, start: $start:expr, $l:expr, $r:expr) => {
+        let (result, times) = run_timed($start, $l, $r, GAMES_PER_SIDE, BOTH_SIDES);
+        // archive every game with the time of each move
+        for (replay, times) in result.replays.iter().zip(&times) {
+            if let Some(record) = Archive::archive(replay, times) {
+                $buf.games
+                    .write_fmt(format_args!("{}\n", record))
+                    .unwrap();
+            }
+        }
+        $buf.results
+            .write_fmt(format_args!("{:?}", x(result)))
+            .unwrap();
+    };
+    // runs the bots on hexapawn, monomorphised for the size
     ($buf:expr, $size:expr, $l:expr, $r:expr) => {
-        $buf.write_fmt(format_args!(
-            "{:?}",
-            x(run(
-                || Board::new($size),
-                $l,
-                $r,
-                GAMES_PER_SIDE,
-                BOTH_SIDES,
-                |_, _| {}
-            ))
-        ))
-        .unwrap();
+        with_size!($size, N => {
+            r!($buf, start: || SizedBoard::<N>::new().with_rules(RULES), $l, $r);
+        })
     };
 }
 
@@ -913,7 +1576,7 @@ impl Debug for BotResult {
         // writeln!(f, "L: {} W{}|D{}|L{} R: {}", self.debug_l, self.wdl_l.win, self.wdl_l.draw, self.wdl_l.loss, self.debug_r)
         writeln!(
             f,
-            "L: {} (t: {:.4}) | W:{},D:{},L:{} | R: {} (t: {:.4})",
+            "L: {} (t: {:.4}ms) | W:{},D:{},L:{} | R: {} (t: {:.4}ms)",
             self.debug_l,
             self.time_l,
             self.wdl_l.win,
@@ -924,12 +1587,13 @@ impl Debug for BotResult {
         )
     }
 }
-pub fn x(result: BotGameResult<Board>) -> BotResult {
+pub fn x<B: BoardTrait>(result: BotGameResult<B>) -> BotResult {
     BotResult {
         wdl_l: result.wdl_l,
         debug_l: result.debug_l,
         debug_r: result.debug_r,
-        time_l: result.time_l,
-        time_r: result.time_r,
+        // the time per move in milliseconds, since most bots take well under a millisecond
+        time_l: result.time_l * 1000.0,
+        time_r: result.time_r * 1000.0,
     }
 }
@@ -1,16 +1,10 @@
 //! Move generation for hexapawn
 
-use std::{cmp::Ordering, fmt::Display};
+use std::{cmp::Ordering, error::Error, fmt::Display, str::FromStr};
 
-use chess::{get_pawn_attacks, get_pawn_quiets, BitBoard, Square};
+use chess::{BitBoard, Color, Square};
 
-use crate::{board::Board, consts::EMPTY_BB};
-
-/// A struct containing a square (the position of a pawn) and a bitboard (possible moves)
-pub struct SquareAndBitBoard {
-    sq: Square,
-    bb: BitBoard,
-}
+use crate::{bitboard::BitBoardBackend, board::Board};
 
 /// Move generation masks
 pub enum Mask {
@@ -22,30 +16,116 @@ pub enum Mask {
     Push,
 }
 
-/// Incremental move generation through Iterator
-pub struct MoveGen {
-    /// All pieces to move and their possible moves
-    moves: Vec<SquareAndBitBoard>,
-    /// The current SquareAndBitBoard used for move gen
-    index: usize,
+/// The destinations of all pawns of one side moving one square forward or capturing sideways, generated set-wise.
+/// Each direction is a shift of the whole pawn bitboard, so a destination has only one source
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PawnTargets<BB: BitBoardBackend = BitBoard> {
+    /// Pushes one square straight forward
+    pub straight: BB,
+    /// Pushes two squares straight forward, from the starting rank
+    pub double: BB,
+    /// Moves diagonally forward towards file A
+    pub left: BB,
+    /// Moves diagonally forward away from file A
+    pub right: BB,
+    /// Captures one square sideways towards file A
+    pub sideways_left: BB,
+    /// Captures one square sideways away from file A
+    pub sideways_right: BB,
+}
+
+/// Incremental move generation through Iterator, without allocating.
+/// The moves are generated set-wise with [`PawnTargets`](PawnTargets),
+/// then iterated by direction: straight, double, left, right, then sideways left and right
+#[derive(Clone, Copy)]
+pub struct MoveGen<BB: BitBoardBackend = BitBoard> {
+    /// The side to move
+    color: Color,
+    /// The destinations left in each direction
+    targets: PawnTargets<BB>,
 }
 
 /// A move
 #[derive(Clone, Copy, Eq, PartialEq, Default, Debug, Hash)]
-pub struct Move {
+pub struct Move<BB: BitBoardBackend = BitBoard> {
     /// From which square
-    src: Square,
+    src: BB::Square,
     /// To which square
-    dest: Square,
+    dest: BB::Square,
 }
 
-impl Display for Move {
+impl<BB: BitBoardBackend> Display for Move<BB> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}{}", self.src, self.dest)
     }
 }
 
-impl Ord for Move {
+/// Parses a square like `a1` or `k11` into its index, if it fits in the bitboard
+fn parse_square<BB: BitBoardBackend>(s: &str) -> Option<usize> {
+    let mut chars = s.chars();
+    let file = chars.next()?;
+    let rank = chars.as_str();
+    // no signs or leading zeros
+    if !file.is_ascii_lowercase() || !rank.starts_with(|c: char| ('1'..='9').contains(&c)) {
+        return None;
+    }
+
+    let file = (file as u8 - b'a') as usize;
+    let rank = rank.parse::<usize>().ok()? - 1;
+    if file < BB::STRIDE && rank < BB::MAX_SIZE {
+        Some(rank * BB::STRIDE + file)
+    } else {
+        None
+    }
+}
+
+impl<BB: BitBoardBackend> FromStr for Move<BB> {
+    type Err = MoveError<BB>;
+
+    /// Parses a move from its source and destination squares, like `a1a2` or `a10b11`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let syntax_err = || MoveError::Syntax(s.to_string());
+        if !s.is_ascii() {
+            return Err(syntax_err());
+        }
+
+        // the destination starts at the second file letter
+        let split = s
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| c.is_ascii_alphabetic())
+            .map(|(i, _)| i)
+            .ok_or_else(syntax_err)?;
+        let src = parse_square::<BB>(&s[..split]).ok_or_else(syntax_err)?;
+        let dest = parse_square::<BB>(&s[split..]).ok_or_else(syntax_err)?;
+        Ok(Move::from_squares(BB::square(src), BB::square(dest)))
+    }
+}
+
+/// An error from parsing a [`Move`](Move)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveError<BB: BitBoardBackend = BitBoard> {
+    /// The text isn't two squares, like `a1a2`
+    Syntax(String),
+    /// A square of the move is outside of the board
+    OffBoard(BB::Square),
+    /// The move isn't legal in the position
+    Illegal(Move<BB>),
+}
+
+impl<BB: BitBoardBackend> Display for MoveError<BB> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveError::Syntax(s) => write!(f, "invalid move '{s}', expected a move like 'a1a2'"),
+            MoveError::OffBoard(sq) => write!(f, "square {sq} is outside of the board"),
+            MoveError::Illegal(mv) => write!(f, "move {mv} is illegal"),
+        }
+    }
+}
+
+impl<BB: BitBoardBackend> Error for MoveError<BB> {}
+
+impl<BB: BitBoardBackend> Ord for Move<BB> {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         // if sources aren't equal, compare them
         if self.src != other.src {
@@ -60,7 +140,7 @@ impl Ord for Move {
     }
 }
 
-impl PartialOrd for Move {
+impl<BB: BitBoardBackend> PartialOrd for Move<BB> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
@@ -71,85 +151,262 @@ impl Move {
     pub fn new(src: Square, dest: Square) -> Move {
         Move { src, dest }
     }
+}
+
+impl<BB: BitBoardBackend> Move<BB> {
+    /// Creates a new `Move` from a source and destination of any bitboard backend
+    pub fn from_squares(src: BB::Square, dest: BB::Square) -> Self {
+        Move { src, dest }
+    }
 
     /// Returns the source of the `Move`
-    pub fn src(&self) -> Square {
+    pub fn src(&self) -> BB::Square {
         self.src
     }
 
     /// Returns the destination of the `Move`
-    pub fn dest(&self) -> Square {
+    pub fn dest(&self) -> BB::Square {
         self.dest
     }
+
+    /// Returns the bitboard index of the source
+    pub fn src_index(&self) -> usize {
+        BB::index(self.src)
+    }
+
+    /// Returns the bitboard index of the destination
+    pub fn dest_index(&self) -> usize {
+        BB::index(self.dest)
+    }
 }
 
-impl MoveGen {
-    /// Creates a new `MoveGen` with a move generation mask
-    pub fn with_mask(board: &Board, mask: Mask) -> MoveGen {
-        let mut movelist = vec![];
-        // for every piece to move
-        for src in board.pieces_to_move() {
-            let moves = match mask {
-                // pawn captures and pushes
-                Mask::None => {
-                    get_pawn_quiets(src, board.side_to_move(), board.occupied())
-                        ^ get_pawn_attacks(src, board.side_to_move(), board.pieces_not_to_move())
-                }
-                // only pawn captures
-                Mask::Capture => {
-                    get_pawn_attacks(src, board.side_to_move(), board.pieces_not_to_move())
-                }
-                // only pawn pushes
-                Mask::Push => get_pawn_quiets(src, board.side_to_move(), board.occupied()),
-            };
-            // if there are moves, add it
-            if moves != EMPTY_BB {
-                movelist.push(SquareAndBitBoard { sq: src, bb: moves })
-            }
+/// Returns the squares in front of `pawns`
+pub(crate) fn forward<BB: BitBoardBackend>(pawns: BB, color: Color) -> BB {
+    match color {
+        Color::White => pawns.shift_up(BB::STRIDE),
+        Color::Black => pawns.shift_down(BB::STRIDE),
+    }
+}
+
+impl<BB: BitBoardBackend> PawnTargets<BB> {
+    /// Generates the destinations of `pawns` moving forward on a board `width` files wide,
+    /// straight to `straight` or diagonally to `diagonal`.
+    /// Pawns on the `double_step` rank can also push two squares straight
+    pub fn generate(
+        pawns: BB,
+        color: Color,
+        width: usize,
+        straight: BB,
+        diagonal: BB,
+        double_step: Option<usize>,
+    ) -> Self {
+        let single = forward(pawns, color) & straight;
+        let double = match double_step {
+            Some(rank) => forward(single & forward(BB::rank(rank), color), color) & straight,
+            None => BB::EMPTY,
+        };
+        // pawns on the edge files don't wrap around to the neighbouring rank
+        let left = forward(pawns & !BB::file(0), color).shift_down(1) & diagonal;
+        let right = forward(pawns & !BB::file(width - 1), color).shift_up(1) & diagonal;
+
+        PawnTargets {
+            straight: single,
+            double,
+            left,
+            right,
+            sideways_left: BB::EMPTY,
+            sideways_right: BB::EMPTY,
+        }
+    }
+
+    /// Adds the captures of `pawns` one square sideways onto `targets`, on a board `width` files wide
+    pub fn with_sideways(self, pawns: BB, width: usize, targets: BB) -> Self {
+        PawnTargets {
+            sideways_left: (pawns & !BB::file(0)).shift_down(1) & targets,
+            sideways_right: (pawns & !BB::file(width - 1)).shift_up(1) & targets,
+            ..self
         }
+    }
+
+    /// Returns the destinations of every direction
+    pub fn all(&self) -> BB {
+        self.straight
+            | self.double
+            | self.left
+            | self.right
+            | self.sideways_left
+            | self.sideways_right
+    }
+
+    /// Returns the amount of moves
+    pub fn len(&self) -> usize {
+        [
+            self.straight,
+            self.double,
+            self.left,
+            self.right,
+            self.sideways_left,
+            self.sideways_right,
+        ]
+        .into_iter()
+        .map(|bb| bb.pop_count() as usize)
+        .sum()
+    }
+
+    /// Returns whether there are no moves
+    pub fn is_empty(&self) -> bool {
+        self.all().is_empty()
+    }
+}
+
+impl<BB: BitBoardBackend> MoveGen<BB> {
+    /// Creates a new `MoveGen` with a move generation mask
+    pub fn with_mask(board: &Board<BB>, mask: Mask) -> Self {
+        MoveGen::with_playable_area(board, mask, board.mask())
+    }
 
-        MoveGen {
-            moves: movelist,
-            index: 0,
+    /// Creates a new `MoveGen` with a move generation mask and the playing area of the board,
+    /// so boards with a constant playing area don't build it for every position
+    pub fn with_playable_area(board: &Board<BB>, mask: Mask, playable: BB) -> Self {
+        let color = board.side_to_move();
+        let pawns = board.pieces_to_move();
+        // pawns on the top rank can't move off the board, `playable` is the whole board
+        // pawns capture diagonally, including en passant
+        let mut targets = board.pieces_not_to_move();
+        if let Some(sq) = board.en_passant() {
+            targets |= BB::from_index(BB::index(sq));
+        }
+        let mut captures = PawnTargets::generate(
+            pawns,
+            color,
+            board.width(),
+            BB::EMPTY,
+            targets & playable,
+            None,
+        );
+        // and sideways onto pawns beside them with sideways capture
+        if board.rules().sideways_capture {
+            captures =
+                captures.with_sideways(pawns, board.width(), board.pieces_not_to_move() & playable);
         }
+
+        // with forced captures, pawns can only push if no pawn can capture
+        let can_push = !board.rules().forced_capture || captures.is_empty();
+        let pushes = match mask {
+            Mask::Capture => BB::EMPTY,
+            _ if !can_push => BB::EMPTY,
+            _ => board.empty() & playable,
+        };
+        // pawns on their starting rank can push two squares with double step
+        let double_step = board.rules().double_step.then(|| match color {
+            Color::White => 0,
+            Color::Black => board.height() - 1,
+        });
+        let pushes =
+            PawnTargets::generate(pawns, color, board.width(), pushes, BB::EMPTY, double_step);
+
+        let targets = match mask {
+            // pawn captures and pushes
+            Mask::None => PawnTargets {
+                left: captures.left,
+                right: captures.right,
+                sideways_left: captures.sideways_left,
+                sideways_right: captures.sideways_right,
+                ..pushes
+            },
+            // only pawn captures
+            Mask::Capture => captures,
+            // only pawn pushes
+            Mask::Push => pushes,
+        };
+        MoveGen::from_targets(color, targets)
     }
 
     /// Creates a new `MoveGen` with no mask
-    pub fn new(board: &Board) -> MoveGen {
+    pub fn new(board: &Board<BB>) -> Self {
         MoveGen::with_mask(board, Mask::None)
     }
-}
 
-impl ExactSizeIterator for MoveGen {
-    fn len(&self) -> usize {
-        let mut len = 0;
-        for moves in &self.moves {
-            if moves.bb == EMPTY_BB {
-                break;
-            }
+    /// Creates a new `MoveGen` from the destinations of `color`'s pawns
+    pub fn from_targets(color: Color, targets: PawnTargets<BB>) -> Self {
+        MoveGen { color, targets }
+    }
 
-            len += moves.bb.popcnt() as usize;
+    /// Returns whether there are any moves left, without generating them
+    pub fn has_any_move(&self) -> bool {
+        !self.targets.is_empty()
+    }
+
+    /// Keeps only the moves to `destinations`
+    pub fn with_destinations(mut self, destinations: BB) -> Self {
+        for bb in self.directions().map(|(bb, _, _)| bb) {
+            *bb &= destinations;
+        }
+        self
+    }
+
+    /// Removes a move, if it's left
+    pub fn remove(&mut self, mv: Move<BB>) {
+        let color = self.color;
+        let dest = mv.dest_index();
+        for (bb, ranks, files) in self.directions() {
+            if source::<BB>(color, dest, ranks, files) == mv.src_index() {
+                *bb &= !BB::from_index(dest);
+            }
         }
-        len
+    }
+
+    /// Returns the destinations of each direction, with how many ranks and files back their sources are
+    fn directions(&mut self) -> [(&mut BB, usize, isize); 6] {
+        [
+            (&mut self.targets.straight, 1, 0),
+            (&mut self.targets.double, 2, 0),
+            (&mut self.targets.left, 1, 1),
+            (&mut self.targets.right, 1, -1),
+            (&mut self.targets.sideways_left, 0, 1),
+            (&mut self.targets.sideways_right, 0, -1),
+        ]
     }
 }
 
-impl Iterator for MoveGen {
-    type Item = Move;
+/// Returns the source of a move to `dest` by `color`, from `ranks` and `files` back
+fn source<BB: BitBoardBackend>(color: Color, dest: usize, ranks: usize, files: isize) -> usize {
+    // black moves down the board, so its sources are above
+    match color {
+        Color::White => dest.wrapping_sub(ranks * BB::STRIDE),
+        Color::Black => dest + ranks * BB::STRIDE,
+    }
+    .wrapping_add_signed(files)
+}
+
+impl<BB: BitBoardBackend> ExactSizeIterator for MoveGen<BB> {
+    fn len(&self) -> usize {
+        self.targets.len()
+    }
+}
+
+impl<BB: BitBoardBackend> Iterator for MoveGen<BB> {
+    type Item = Move<BB>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        // Get the currently used SquareAndBitBoard. if there's none,finish
-        let moves = &mut self.moves.get_mut(self.index)?;
-        // Get the least signifigant ones bit
-        let dest = moves.bb.to_square();
+        let color = self.color;
+        // the first direction with moves left
+        let (bb, ranks, files) = self
+            .directions()
+            .into_iter()
+            .find(|(bb, _, _)| !bb.is_empty())?;
 
-        // remove that bit from the SquareAndBitBoard
-        moves.bb ^= BitBoard::from_square(dest);
-        // if the SquareAndBitBoard is out of moves, increment the index
-        if moves.bb == EMPTY_BB {
-            self.index += 1;
-        }
+        // Get the least signifigant ones bit
+        let dest = bb.lsb();
+        // remove that bit from the direction
+        *bb ^= BB::from_index(dest);
         // create a move from the square to move and a possible move
-        Some(Move::new(moves.sq, dest))
+        let src = source::<BB>(color, dest, ranks, files);
+        Some(Move::from_squares(BB::square(src), BB::square(dest)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
     }
 }
@@ -0,0 +1,229 @@
+//! Staged and ordered move generation for search bots
+//!
+//! [`StagedMoveGen`](StagedMoveGen) yields winning moves first, then captures, then pushes.
+//! Within a stage, moves can be ordered by a [`MoveOrder`](MoveOrder),
+//! like [`Killers`](Killers) and [`History`](History) scores
+
+use std::marker::PhantomData;
+
+use chess::BitBoard;
+
+use crate::bitboard::BitBoardBackend;
+use crate::move_gen::{Mask, Move, MoveGen};
+use crate::pawn_board::PawnBoard;
+
+/// Scores moves to order them within a stage of a [`StagedMoveGen`](StagedMoveGen), higher first
+pub trait MoveOrder<BB: BitBoardBackend = BitBoard> {
+    /// Returns the score of a move
+    fn score(&self, mv: Move<BB>) -> i32;
+}
+
+impl<BB: BitBoardBackend, F: Fn(Move<BB>) -> i32> MoveOrder<BB> for F {
+    fn score(&self, mv: Move<BB>) -> i32 {
+        self(mv)
+    }
+}
+
+/// The stages of a [`StagedMoveGen`](StagedMoveGen), in the order they're generated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Stage {
+    /// Moves to the far rank, which win
+    Wins,
+    /// Captures that don't reach the far rank
+    Captures,
+    /// Pushes that don't reach the far rank
+    Pushes,
+    /// Moves to the far rank in [misère](crate::rules::Rules::misere), which lose
+    Losses,
+}
+
+/// Move generation in stages: wins, captures, then pushes.
+/// In misère, moves to the far rank lose, so they're generated last instead of first
+pub struct StagedMoveGen<'a, BB: BitBoardBackend = BitBoard> {
+    /// All the available moves
+    moves: MoveGen<BB>,
+    /// The stages and the destinations of their moves
+    stages: [(Stage, BB); 3],
+    /// How many of `stages` have been started
+    next_stage: usize,
+    /// The stage being generated
+    stage: Stage,
+    /// The moves left in the current stage
+    current: MoveGen<BB>,
+    /// Orders the moves within each stage, or none to keep the order of [`MoveGen`](MoveGen)
+    order: Option<&'a dyn MoveOrder<BB>>,
+}
+
+impl<'a, BB: BitBoardBackend> StagedMoveGen<'a, BB> {
+    /// Creates a new `StagedMoveGen` without ordering within stages
+    pub fn new<B: PawnBoard<BB = BB>>(board: &B) -> Self {
+        let color = board.side_to_move();
+        let moves = board.move_gen(Mask::None);
+        let far = board.win_rank(color);
+        let captures = board.capture_squares() & !far;
+        let pushes = !far & !captures;
+
+        let stages = if board.misere() {
+            [
+                (Stage::Captures, captures),
+                (Stage::Pushes, pushes),
+                (Stage::Losses, far),
+            ]
+        } else {
+            [
+                (Stage::Wins, far),
+                (Stage::Captures, captures),
+                (Stage::Pushes, pushes),
+            ]
+        };
+
+        StagedMoveGen {
+            moves,
+            stages,
+            next_stage: 0,
+            stage: stages[0].0,
+            // the first stage starts with the first move
+            current: moves.with_destinations(BB::EMPTY),
+            order: None,
+        }
+    }
+
+    /// Creates a new `StagedMoveGen` that orders the moves within each stage
+    pub fn with_order<B: PawnBoard<BB = BB>>(board: &B, order: &'a dyn MoveOrder<BB>) -> Self {
+        StagedMoveGen {
+            order: Some(order),
+            ..StagedMoveGen::new(board)
+        }
+    }
+
+    /// Returns the stage being generated
+    pub fn stage(&self) -> Stage {
+        self.stage
+    }
+}
+
+impl<'a, BB: BitBoardBackend> Iterator for StagedMoveGen<'a, BB> {
+    type Item = Move<BB>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // move on to the next stage with moves
+        while !self.current.has_any_move() {
+            let &(stage, destinations) = self.stages.get(self.next_stage)?;
+            self.next_stage += 1;
+            self.stage = stage;
+            self.current = self.moves.with_destinations(destinations);
+        }
+
+        match self.order {
+            // the first of the best scored moves
+            Some(order) => {
+                let mut best = None;
+                for mv in self.current {
+                    let score = order.score(mv);
+                    if best.is_none_or(|(_, best)| score > best) {
+                        best = Some((mv, score));
+                    }
+                }
+                let (mv, _) = best?;
+                self.current.remove(mv);
+                Some(mv)
+            }
+            None => self.current.next(),
+        }
+    }
+}
+
+/// [Killer moves](https://www.chessprogramming.org/Killer_Heuristic):
+/// the last two quiet moves that caused a cutoff at each ply
+#[derive(Debug, Clone, Default)]
+pub struct Killers<BB: BitBoardBackend = BitBoard> {
+    /// The two killer moves of each ply, the most recent first
+    killers: Vec<[Option<Move<BB>>; 2]>,
+}
+
+impl<BB: BitBoardBackend> Killers<BB> {
+    /// The score of the most recent killer move
+    pub const FIRST: i32 = 2_000_000;
+    /// The score of the other killer move
+    pub const SECOND: i32 = 1_000_000;
+
+    /// Creates empty `Killers`
+    pub fn new() -> Self {
+        Killers { killers: vec![] }
+    }
+
+    /// Stores a move that caused a cutoff at a ply
+    pub fn store(&mut self, ply: usize, mv: Move<BB>) {
+        if self.killers.len() <= ply {
+            self.killers.resize(ply + 1, [None; 2]);
+        }
+
+        let killers = &mut self.killers[ply];
+        if killers[0] != Some(mv) {
+            killers[1] = killers[0];
+            killers[0] = Some(mv);
+        }
+    }
+
+    /// Returns the score of a move at a ply, [`FIRST`](Killers::FIRST) or [`SECOND`](Killers::SECOND)
+    /// for killer moves and 0 for other moves
+    pub fn score(&self, ply: usize, mv: Move<BB>) -> i32 {
+        match self.killers.get(ply) {
+            Some([first, _]) if *first == Some(mv) => Self::FIRST,
+            Some([_, second]) if *second == Some(mv) => Self::SECOND,
+            _ => 0,
+        }
+    }
+}
+
+/// The [history heuristic](https://www.chessprogramming.org/History_Heuristic):
+/// how often each move caused a cutoff, weighted by depth
+#[derive(Debug, Clone)]
+pub struct History<BB: BitBoardBackend = BitBoard> {
+    /// The score of each move, by source and destination index
+    scores: Vec<i32>,
+    bb: PhantomData<BB>,
+}
+
+impl<BB: BitBoardBackend> Default for History<BB> {
+    fn default() -> Self {
+        History::new()
+    }
+}
+
+impl<BB: BitBoardBackend> History<BB> {
+    /// The amount of squares of the bitboard
+    const SQUARES: usize = BB::STRIDE * BB::MAX_SIZE;
+
+    /// Creates an empty `History`
+    pub fn new() -> Self {
+        History {
+            scores: vec![0; Self::SQUARES * Self::SQUARES],
+            bb: PhantomData,
+        }
+    }
+
+    /// Returns the index of a move in `scores`
+    fn index(mv: Move<BB>) -> usize {
+        mv.src_index() * Self::SQUARES + mv.dest_index()
+    }
+
+    /// Adds a cutoff of a move searched to a depth
+    pub fn add(&mut self, mv: Move<BB>, depth: u32) {
+        let score = &mut self.scores[Self::index(mv)];
+        *score = score.saturating_add((depth * depth) as i32);
+    }
+
+    /// Halves every score, so older cutoffs count less
+    pub fn age(&mut self) {
+        for score in &mut self.scores {
+            *score /= 2;
+        }
+    }
+}
+
+impl<BB: BitBoardBackend> MoveOrder<BB> for History<BB> {
+    fn score(&self, mv: Move<BB>) -> i32 {
+        self.scores[Self::index(mv)]
+    }
+}
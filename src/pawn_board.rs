@@ -0,0 +1,85 @@
+//! The [`PawnBoard`](PawnBoard) trait shared by [hexapawn](crate::board) and [breakthrough](crate::breakthrough)
+
+use board_game::board::Board as BoardTrait;
+use chess::Color;
+
+use crate::bitboard::BitBoardBackend;
+use crate::move_gen::{Mask, Move, MoveGen};
+
+/// A board of white and black pawns stored in bitboards, moving towards the far rank.
+/// The heuristics and heuristic bots in [`bot`](crate::bot) run on any `PawnBoard`
+pub trait PawnBoard: BoardTrait<Move = Move<<Self as PawnBoard>::BB>> {
+    /// The bitboard the pawns are stored in
+    type BB: BitBoardBackend;
+
+    /// Returns the bitboard of a certain color's pawns
+    fn pieces(&self, color: Color) -> Self::BB;
+
+    /// Returns the side to move, White or Black
+    fn side_to_move(&self) -> Color;
+
+    /// Returns a bitboard of the playing area
+    fn mask(&self) -> Self::BB;
+
+    /// Generates the available moves, only captures or only non-captures with a [`Mask`](Mask)
+    fn move_gen(&self, mask: Mask) -> MoveGen<Self::BB>;
+
+    /// Returns the available moves, only captures or only non-captures with a [`Mask`](Mask)
+    fn masked_moves(&self, mask: Mask) -> Vec<Move<Self::BB>> {
+        self.move_gen(mask).collect()
+    }
+
+    /// Returns the rank a color has to reach to win
+    fn win_rank(&self, color: Color) -> Self::BB;
+
+    /// Returns the squares the side to move captures on by moving there
+    fn capture_squares(&self) -> Self::BB {
+        self.pieces_not_to_move()
+    }
+
+    /// Returns whether reaching the far rank loses instead of wins, like [misère](crate::rules::Rules::misere)
+    fn misere(&self) -> bool {
+        false
+    }
+
+    /// Returns the bitboard of the pawns to move
+    fn pieces_to_move(&self) -> Self::BB {
+        self.pieces(self.side_to_move())
+    }
+
+    /// Returns the bitboard of the pawns not to move
+    fn pieces_not_to_move(&self) -> Self::BB {
+        self.pieces(!self.side_to_move())
+    }
+}
+
+/// Draws the pawns from the top rank down, followed by the file letters and numbers
+pub(crate) fn fmt_pawns<BB: BitBoardBackend>(
+    f: &mut std::fmt::Formatter<'_>,
+    white: BB,
+    black: BB,
+    width: usize,
+    height: usize,
+) -> std::fmt::Result {
+    // from the top rank down
+    for rank in (0..height).rev() {
+        for file in 0..width {
+            let bb = BB::from_index(rank * BB::STRIDE + file);
+
+            match (!(white & bb).is_empty(), !(black & bb).is_empty()) {
+                (false, false) => f.write_str(" ")?,
+                (true, false) => f.write_str("♙")?, // white pawn
+                (false, true) => f.write_str("♟︎")?, // black pawn
+                (true, true) => panic!("Board has two pawns in the same place"),
+            }
+        }
+
+        f.write_fmt(format_args!("|{}\n", rank + 1))?;
+    }
+
+    writeln!(f, "{}*", "-".repeat(width))?;
+    writeln!(f, "{}", &"abcdefghijk"[..width])?;
+    writeln!(f, "{}", &"0123456789A"[..width])?;
+
+    Ok(())
+}
@@ -0,0 +1,237 @@
+//! [Perft](https://www.chessprogramming.org/Perft), counting the leaf nodes of the game tree
+//! to check move generation, and the reference values it's checked against
+
+use board_game::board::Board as BoardTrait;
+use internal_iterator::InternalIterator;
+
+use crate::bitboard::BitBoardBackend;
+use crate::board::Board;
+use crate::move_gen::MoveGen;
+use crate::rules::Rules;
+
+/// Returns the amount of leaf nodes `depth` plies from `board`.
+/// Games that end before `depth` don't count
+pub fn perft<B: BoardTrait>(board: &B, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    if board.is_done() {
+        return 0;
+    }
+    // bulk count the last ply
+    if depth == 1 {
+        return board.available_moves().count() as u64;
+    }
+
+    let mut nodes = 0;
+    board.available_moves().for_each(|mv| {
+        nodes += perft(&board.clone_and_play(mv), depth - 1);
+    });
+    nodes
+}
+
+/// Perft with [`Board::make`](Board::make) and [`Board::unmake`](Board::unmake) instead of cloning,
+/// leaving `board` as it was
+pub fn perft_make_unmake<BB: BitBoardBackend>(board: &mut Board<BB>, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    if board.is_done() {
+        return 0;
+    }
+
+    let mut nodes = 0;
+    for mv in MoveGen::new(board) {
+        let undo = board.make(mv);
+        nodes += perft_make_unmake(board, depth - 1);
+        board.unmake(mv, undo);
+    }
+    nodes
+}
+
+/// Returns the perft of each move from `board`, in the order they're generated
+pub fn divide<B: BoardTrait>(board: &B, depth: u32) -> Vec<(B::Move, u64)> {
+    if depth == 0 || board.is_done() {
+        return vec![];
+    }
+
+    let mut divide = vec![];
+    board.available_moves().for_each(|mv| {
+        divide.push((mv, perft(&board.clone_and_play(mv), depth - 1)));
+    });
+    divide
+}
+
+/// Reference perft values of a size and rule set, from depth 1
+#[derive(Debug, Clone, Copy)]
+pub struct PerftReference {
+    /// The size of the board
+    pub size: usize,
+    /// The rules, like `standard` or `double-step en-passant`
+    pub rules: &'static str,
+    /// The perft values, from depth 1
+    pub nodes: &'static [u64],
+}
+
+impl PerftReference {
+    /// Returns the starting position
+    pub fn board(&self) -> Board {
+        let rules: Rules = self.rules.parse().unwrap();
+        Board::new(self.size).with_rules(rules)
+    }
+}
+
+/// Reference perft values for every size in [`SIZES`](crate::SIZES) and the variant rules.
+/// [Misère](crate::rules::Rules::misere) and the stalemate rules play the same games as standard rules
+pub const REFERENCE: &[PerftReference] = &[
+    PerftReference {
+        size: 3,
+        rules: "standard",
+        nodes: &[3, 10, 28, 56, 70, 64, 20, 0],
+    },
+    PerftReference {
+        size: 4,
+        rules: "standard",
+        nodes: &[4, 16, 66, 280, 1170, 4548, 16684, 53706, 155708, 369328],
+    },
+    PerftReference {
+        size: 5,
+        rules: "standard",
+        nodes: &[5, 25, 125, 628, 3180, 16270, 83654, 427414],
+    },
+    PerftReference {
+        size: 6,
+        rules: "standard",
+        nodes: &[6, 36, 216, 1296, 7780, 46770, 281882],
+    },
+    PerftReference {
+        size: 7,
+        rules: "standard",
+        nodes: &[7, 49, 343, 2401, 16807, 117654],
+    },
+    PerftReference {
+        size: 8,
+        rules: "standard",
+        nodes: &[8, 64, 512, 4096, 32768, 262144],
+    },
+    PerftReference {
+        size: 3,
+        rules: "double-step",
+        nodes: &[3, 10, 32, 62, 80, 68, 20, 0],
+    },
+    PerftReference {
+        size: 4,
+        rules: "double-step",
+        nodes: &[8, 58, 354, 1698, 6998, 24088, 76720, 211284],
+    },
+    PerftReference {
+        size: 5,
+        rules: "double-step",
+        nodes: &[10, 95, 821, 6798, 51594, 371906],
+    },
+    PerftReference {
+        size: 6,
+        rules: "double-step",
+        nodes: &[12, 144, 1588, 17486, 177604],
+    },
+    PerftReference {
+        size: 7,
+        rules: "double-step",
+        nodes: &[14, 196, 2548, 33129, 400339],
+    },
+    PerftReference {
+        size: 8,
+        rules: "double-step",
+        nodes: &[16, 256, 3840, 57600],
+    },
+    PerftReference {
+        size: 3,
+        rules: "double-step en-passant",
+        nodes: &[3, 10, 32, 62, 80, 68, 20, 0],
+    },
+    PerftReference {
+        size: 4,
+        rules: "double-step en-passant",
+        nodes: &[8, 58, 360, 1784, 7746, 28322, 93808, 266738],
+    },
+    PerftReference {
+        size: 5,
+        rules: "double-step en-passant",
+        nodes: &[10, 95, 829, 6932, 53618, 392066],
+    },
+    PerftReference {
+        size: 6,
+        rules: "double-step en-passant",
+        nodes: &[12, 144, 1588, 17486, 177712],
+    },
+    PerftReference {
+        size: 7,
+        rules: "double-step en-passant",
+        nodes: &[14, 196, 2548, 33129, 400339],
+    },
+    PerftReference {
+        size: 8,
+        rules: "double-step en-passant",
+        nodes: &[16, 256, 3840, 57600],
+    },
+    PerftReference {
+        size: 3,
+        rules: "forced-capture",
+        nodes: &[3, 4, 6, 6, 4, 0],
+    },
+    PerftReference {
+        size: 4,
+        rules: "forced-capture",
+        nodes: &[4, 16, 42, 84, 146, 268, 428, 640, 848, 1012],
+    },
+    PerftReference {
+        size: 5,
+        rules: "forced-capture",
+        nodes: &[5, 25, 125, 588, 2554, 9954, 33934, 100274, 255252],
+    },
+    PerftReference {
+        size: 6,
+        rules: "forced-capture",
+        nodes: &[6, 36, 216, 1296, 7720, 45228, 257932],
+    },
+    PerftReference {
+        size: 7,
+        rules: "forced-capture",
+        nodes: &[7, 49, 343, 2401, 16807, 117570],
+    },
+    PerftReference {
+        size: 8,
+        rules: "forced-capture",
+        nodes: &[8, 64, 512, 4096, 32768, 262144],
+    },
+    PerftReference {
+        size: 3,
+        rules: "sideways-capture",
+        nodes: &[3, 10, 32, 84, 176, 258, 350, 262, 172, 76, 24, 0],
+    },
+    PerftReference {
+        size: 4,
+        rules: "sideways-capture",
+        nodes: &[4, 16, 66, 286, 1266, 5400, 22678, 87240, 318894],
+    },
+    PerftReference {
+        size: 5,
+        rules: "sideways-capture",
+        nodes: &[5, 25, 125, 628, 3188, 16474, 86576, 459074],
+    },
+    PerftReference {
+        size: 6,
+        rules: "sideways-capture",
+        nodes: &[6, 36, 216, 1296, 7780, 46780, 282232],
+    },
+    PerftReference {
+        size: 7,
+        rules: "sideways-capture",
+        nodes: &[7, 49, 343, 2401, 16807, 117654],
+    },
+    PerftReference {
+        size: 8,
+        rules: "sideways-capture",
+        nodes: &[8, 64, 512, 4096, 32768, 262144],
+    },
+];
@@ -0,0 +1,647 @@
+//! Game records for hexapawn, in a text format similar to [PGN](https://en.wikipedia.org/wiki/Portable_Game_Notation)
+//!
+//! A record starts with tags, followed by a blank line and the moves:
+//!
+//! ```text
+//! [Size "3"]
+//! [Start "ppp/3/PPP w"]
+//! [White "RandomBot"]
+//! [Black "AlwaysPushBot"]
+//! [Result "0-1"]
+//!
+//! 1. a1a2 {[%emt 0.0001]} b3a2 2. c1c2 {a comment} a2b1 0-1
+//! ```
+//!
+//! The size is a single number for square boards, or the width and height like `4x3`.
+//! Games with [rule variants](crate::rules::Rules) have a tag like `[Rules "double-step en-passant"]`.
+//! Moves may be followed by a comment in braces, which can start with the time taken for the move in seconds.
+//! `}` and `\` in comments are escaped with a backslash.
+//!
+//! A file of records, like the tournament's [games](crate::games_path), has a blank line after each record
+//! and is read with [`GameRecord::parse_all`](GameRecord::parse_all).
+//! [`run_timed`](run_timed) plays the tournament's games and keeps the time of every move for them.
+
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::Instant;
+
+use board_game::ai::Bot;
+use board_game::board::Board as BoardTrait;
+use board_game::board::{Outcome, Player};
+use board_game::util::bot_game::{BotGameResult, Replay};
+use board_game::wdl::POV;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::board::{Board, ParseBoardError};
+use crate::move_gen::{Move, MoveError};
+use crate::rules::{Rules, RulesError};
+
+/// A move in a [`GameRecord`](GameRecord) with an optional time and comment
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedMove {
+    /// The move played
+    pub mv: Move,
+    /// How long the move took in seconds
+    pub time: Option<f32>,
+    /// A comment about the move
+    pub comment: Option<String>,
+}
+
+impl RecordedMove {
+    /// Creates a new `RecordedMove` without a time or comment
+    pub fn new(mv: Move) -> Self {
+        RecordedMove {
+            mv,
+            time: None,
+            comment: None,
+        }
+    }
+}
+
+/// A record of a hexapawn game
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameRecord {
+    /// The starting position
+    pub start: Board,
+    /// The name of the white player
+    pub white: String,
+    /// The name of the black player
+    pub black: String,
+    /// The moves played
+    pub moves: Vec<RecordedMove>,
+    /// The outcome, `None` if the game isn't finished
+    pub outcome: Option<Outcome>,
+}
+
+impl GameRecord {
+    /// Creates a new `GameRecord` with no moves
+    pub fn new(start: Board, white: impl Into<String>, black: impl Into<String>) -> Self {
+        GameRecord {
+            outcome: start.outcome(),
+            start,
+            white: white.into(),
+            black: black.into(),
+            moves: vec![],
+        }
+    }
+
+    /// Returns the width and height of the board
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.start.width(), self.start.height())
+    }
+
+    /// Parses a file of records, each followed by a blank line like the tournament writes them.
+    /// A record ends where the tags of the next one start
+    pub fn parse_all(s: &str) -> Result<Vec<GameRecord>, RecordError> {
+        let mut records = vec![];
+        let mut record = String::new();
+        // whether the moves of the current record have started
+        let mut in_moves = false;
+        for line in s.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') && in_moves {
+                records.push(record.parse()?);
+                record.clear();
+                in_moves = false;
+            } else if !trimmed.is_empty() && !trimmed.starts_with('[') {
+                in_moves = true;
+            }
+            record.push_str(line);
+            record.push('\n');
+        }
+        if !record.trim().is_empty() {
+            records.push(record.parse()?);
+        }
+        Ok(records)
+    }
+
+    /// Sets the time of each move in seconds, in the order they were played
+    pub fn with_times(mut self, times: &[f32]) -> Self {
+        for (mv, &time) in self.moves.iter_mut().zip(times) {
+            mv.time = Some(time);
+        }
+        self
+    }
+
+    /// Returns the position after all moves are played
+    pub fn board(&self) -> Board {
+        let mut board = self.start.clone();
+        for mv in &self.moves {
+            board.play(mv.mv);
+        }
+        board
+    }
+}
+
+/// Plays a game between two bots from `start`, recording the time taken for each move
+pub fn play_game(
+    start: &Board,
+    white: &mut impl Bot<Board>,
+    black: &mut impl Bot<Board>,
+) -> GameRecord {
+    let mut record = GameRecord::new(start.clone(), format!("{white:?}"), format!("{black:?}"));
+    let mut board = start.clone();
+
+    while !board.is_done() {
+        let start_time = Instant::now();
+        let mv = match board.next_player() {
+            Player::A => white.select_move(&board),
+            Player::B => black.select_move(&board),
+        };
+        let time = start_time.elapsed().as_secs_f32();
+
+        board.play(mv);
+        record.moves.push(RecordedMove {
+            mv,
+            time: Some(time),
+            comment: None,
+        });
+    }
+
+    record.outcome = board.outcome();
+    record
+}
+
+/// Runs `bot_l` against `bot_r` in parallel like [`bot_game::run`](board_game::util::bot_game::run),
+/// also returning the time each move took in seconds for every replay, in the same order as the replays
+pub fn run_timed<B: BoardTrait, L: Bot<B>, R: Bot<B>>(
+    start: impl Fn() -> B + Sync,
+    bot_l: impl Fn() -> L + Sync,
+    bot_r: impl Fn() -> R + Sync,
+    games_per_side: u32,
+    both_sides: bool,
+) -> (BotGameResult<B>, Vec<Vec<f32>>) {
+    let debug_l = format!("{:?}", bot_l());
+    let debug_r = format!("{:?}", bot_r());
+
+    let game_count = if both_sides {
+        2 * games_per_side
+    } else {
+        games_per_side
+    };
+    let starts: Vec<B> = (0..games_per_side).map(|_| start()).collect();
+
+    let (replays, times): (Vec<_>, Vec<_>) = (0..game_count)
+        .into_par_iter()
+        .map(|game_i| {
+            // with both sides every start is played twice, the second time with the bots swapped
+            let (start_i, flip) = if both_sides {
+                (game_i / 2, game_i % 2 == 1)
+            } else {
+                (game_i, false)
+            };
+            play_timed(&starts[start_i as usize], flip, &mut bot_l(), &mut bot_r())
+        })
+        .unzip();
+
+    let total_time_l = replays.iter().map(|r| r.total_time_l).sum::<f32>();
+    let total_time_r = replays.iter().map(|r| r.total_time_r).sum::<f32>();
+    let move_count_l = replays.iter().map(|r| r.move_count_l).sum::<u32>();
+    let move_count_r = replays.iter().map(|r| r.move_count_r).sum::<u32>();
+
+    let result = BotGameResult {
+        game_count,
+        average_game_length: replays.iter().map(|r| r.moves.len() as f32).sum::<f32>()
+            / game_count as f32,
+        wdl_l: replays
+            .iter()
+            .map(|r| r.outcome.pov(r.player_l).to_wdl())
+            .sum(),
+        time_l: total_time_l / move_count_l as f32,
+        time_r: total_time_r / move_count_r as f32,
+        debug_l,
+        debug_r,
+        replays,
+    };
+    (result, times)
+}
+
+/// Plays a single game for [`run_timed`](run_timed), `flip` lets the right bot move first
+fn play_timed<B: BoardTrait>(
+    start: &B,
+    flip: bool,
+    bot_l: &mut impl Bot<B>,
+    bot_r: &mut impl Bot<B>,
+) -> (Replay<B>, Vec<f32>) {
+    let mut board = start.clone();
+    let player_l = if flip {
+        board.next_player().other()
+    } else {
+        board.next_player()
+    };
+
+    let mut total_time_l = 0.0;
+    let mut total_time_r = 0.0;
+    let mut move_count_l = 0;
+    let mut move_count_r = 0;
+    let mut moves = vec![];
+    let mut times = vec![];
+
+    let outcome = loop {
+        if let Some(outcome) = board.outcome() {
+            break outcome;
+        }
+
+        let start_time = Instant::now();
+        let mv = if board.next_player() == player_l {
+            bot_l.select_move(&board)
+        } else {
+            bot_r.select_move(&board)
+        };
+        let time = start_time.elapsed().as_secs_f32();
+
+        if board.next_player() == player_l {
+            total_time_l += time;
+            move_count_l += 1;
+        } else {
+            total_time_r += time;
+            move_count_r += 1;
+        }
+        moves.push(mv);
+        times.push(time);
+        board.play(mv);
+    };
+
+    let replay = Replay {
+        start: start.clone(),
+        player_l,
+        moves,
+        outcome,
+        total_time_l,
+        total_time_r,
+        move_count_l,
+        move_count_r,
+        debug_l: format!("{bot_l:?}"),
+        debug_r: format!("{bot_r:?}"),
+    };
+    (replay, times)
+}
+
+impl<B: BoardTrait<Move = Move> + Into<Board>> From<&Replay<B>> for GameRecord {
+    fn from(replay: &Replay<B>) -> Self {
+        // the left bot plays white if it's the first to move
+        let (white, black) = if replay.player_l == Player::A {
+            (&replay.debug_l, &replay.debug_r)
+        } else {
+            (&replay.debug_r, &replay.debug_l)
+        };
+
+        GameRecord {
+            start: replay.start.clone().into(),
+            white: white.clone(),
+            black: black.clone(),
+            moves: replay
+                .moves
+                .iter()
+                .copied()
+                .map(RecordedMove::new)
+                .collect(),
+            outcome: Some(replay.outcome),
+        }
+    }
+}
+
+/// Returns the result token for an outcome
+fn result_str(outcome: Option<Outcome>) -> &'static str {
+    match outcome {
+        Some(Outcome::WonBy(Player::A)) => "1-0",
+        Some(Outcome::WonBy(Player::B)) => "0-1",
+        Some(Outcome::Draw) => "1/2-1/2",
+        None => "*",
+    }
+}
+
+/// Parses a result token, returns `None` if it isn't a result
+fn parse_result(s: &str) -> Option<Option<Outcome>> {
+    match s {
+        "1-0" => Some(Some(Outcome::WonBy(Player::A))),
+        "0-1" => Some(Some(Outcome::WonBy(Player::B))),
+        "1/2-1/2" => Some(Some(Outcome::Draw)),
+        "*" => Some(None),
+        _ => None,
+    }
+}
+
+/// Writes a tag, escaping quotes and backslashes
+fn write_tag(f: &mut std::fmt::Formatter<'_>, name: &str, value: &str) -> std::fmt::Result {
+    let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+    writeln!(f, "[{name} \"{value}\"]")
+}
+
+/// Escapes the backslashes and closing braces of a comment
+fn escape_comment(comment: &str) -> String {
+    comment.replace('\\', "\\\\").replace('}', "\\}")
+}
+
+impl Display for GameRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let size = match self.dimensions() {
+            (width, height) if width == height => width.to_string(),
+            (width, height) => format!("{width}x{height}"),
+        };
+        write_tag(f, "Size", &size)?;
+        write_tag(f, "Start", &self.start.to_notation())?;
+        if self.start.rules() != Rules::STANDARD {
+            write_tag(f, "Rules", &self.start.rules().to_string())?;
+        }
+        write_tag(f, "White", &self.white)?;
+        write_tag(f, "Black", &self.black)?;
+        write_tag(f, "Result", result_str(self.outcome))?;
+        writeln!(f)?;
+
+        let mut board = self.start.clone();
+        // if black starts, white's first move is on move 2
+        let offset = (board.next_player() == Player::B) as usize;
+        for (i, mv) in self.moves.iter().enumerate() {
+            // number white's moves, and the first move if black starts
+            match board.next_player() {
+                Player::A => write!(f, "{}. ", (i + offset) / 2 + 1)?,
+                Player::B if i == 0 => write!(f, "1... ")?,
+                Player::B => {}
+            }
+            write!(f, "{} ", mv.mv)?;
+
+            let comment = mv.comment.as_deref().map(escape_comment);
+            match (mv.time, comment) {
+                (Some(time), Some(comment)) => write!(f, "{{[%emt {time}] {comment}}} ")?,
+                (Some(time), None) => write!(f, "{{[%emt {time}]}} ")?,
+                (None, Some(comment)) => write!(f, "{{{comment}}} ")?,
+                (None, None) => {}
+            }
+
+            board.play(mv.mv);
+        }
+
+        writeln!(f, "{}", result_str(self.outcome))
+    }
+}
+
+/// An error from parsing a [`GameRecord`](GameRecord)
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordError {
+    /// A tag line isn't like `[Name "value"]`
+    InvalidTag(String),
+    /// A required tag is missing
+    MissingTag(&'static str),
+    /// The size tag isn't a valid size
+    InvalidSize(String),
+    /// The size tag doesn't match the size of the start position
+    SizeMismatch {
+        /// The width and height from the size tag
+        size: (usize, usize),
+        /// The width and height of the start position
+        start: (usize, usize),
+    },
+    /// The start position isn't valid
+    InvalidStart(ParseBoardError),
+    /// The rules tag isn't valid
+    InvalidRules(RulesError),
+    /// A result isn't `1-0`, `0-1`, `1/2-1/2` or `*`
+    InvalidResult(String),
+    /// A move (from ply 0) isn't valid
+    InvalidMove {
+        /// The ply of the move
+        ply: usize,
+        /// Why the move isn't valid
+        err: MoveError,
+    },
+    /// A move (from ply 0) is played after the game ended
+    MoveAfterEnd(usize),
+    /// A comment isn't after a move
+    MisplacedComment,
+    /// A comment isn't closed
+    UnclosedComment,
+    /// The time in a comment isn't a number
+    InvalidTime(String),
+    /// The moves don't end with a result
+    MissingResult,
+    /// There is more than whitespace after the result
+    TextAfterResult(String),
+    /// The result tag and the result after the moves are different
+    ResultMismatch,
+    /// The result doesn't match the position after the moves
+    WrongResult,
+}
+
+impl Display for RecordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecordError::InvalidTag(line) => write!(f, "invalid tag '{line}'"),
+            RecordError::MissingTag(name) => write!(f, "missing tag {name}"),
+            RecordError::InvalidSize(size) => write!(f, "invalid size '{size}'"),
+            RecordError::SizeMismatch { size, start } => write!(
+                f,
+                "size is {}x{} but the start position is {}x{}",
+                size.0, size.1, start.0, start.1
+            ),
+            RecordError::InvalidStart(err) => write!(f, "invalid start position: {err}"),
+            RecordError::InvalidRules(err) => write!(f, "invalid rules: {err}"),
+            RecordError::InvalidResult(result) => write!(f, "invalid result '{result}'"),
+            RecordError::InvalidMove { ply, err } => write!(f, "invalid move at ply {ply}: {err}"),
+            RecordError::MoveAfterEnd(ply) => {
+                write!(f, "move at ply {ply} is after the game ended")
+            }
+            RecordError::MisplacedComment => write!(f, "comment before the first move"),
+            RecordError::UnclosedComment => write!(f, "comment isn't closed"),
+            RecordError::InvalidTime(time) => write!(f, "invalid time '{time}'"),
+            RecordError::MissingResult => write!(f, "moves don't end with a result"),
+            RecordError::TextAfterResult(text) => write!(f, "unexpected '{text}' after the result"),
+            RecordError::ResultMismatch => {
+                write!(f, "result tag doesn't match the result after the moves")
+            }
+            RecordError::WrongResult => {
+                write!(f, "result doesn't match the final position")
+            }
+        }
+    }
+}
+
+impl Error for RecordError {}
+
+/// Parses a tag line like `[Name "value"]`
+fn parse_tag(line: &str) -> Option<(&str, String)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+    let (name, value) = inner.split_once(' ')?;
+    let value = value.strip_prefix('"')?.strip_suffix('"')?;
+
+    // unescape quotes and backslashes
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.push(chars.next()?),
+            '"' => return None,
+            _ => unescaped.push(c),
+        }
+    }
+
+    Some((name, unescaped))
+}
+
+/// Splits off an escaped comment up to its closing brace, returns the unescaped comment and the text after it
+fn split_comment(s: &str) -> Option<(String, &str)> {
+    let mut comment = String::new();
+    let mut chars = s.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => comment.push(chars.next()?.1),
+            '}' => return Some((comment, &s[i + 1..])),
+            _ => comment.push(c),
+        }
+    }
+    None
+}
+
+/// Parses a comment into a time and the rest of the comment
+fn parse_comment(comment: &str) -> Result<(Option<f32>, Option<String>), RecordError> {
+    let comment = comment.trim();
+    let (time, rest) = match comment.strip_prefix("[%emt ") {
+        Some(rest) => {
+            let (time, rest) = rest
+                .split_once(']')
+                .ok_or_else(|| RecordError::InvalidTime(rest.to_string()))?;
+            let time = time
+                .trim()
+                .parse()
+                .map_err(|_| RecordError::InvalidTime(time.to_string()))?;
+            (Some(time), rest.trim())
+        }
+        None => (None, comment),
+    };
+
+    let rest = if rest.is_empty() {
+        None
+    } else {
+        Some(rest.to_string())
+    };
+    Ok((time, rest))
+}
+
+/// Parses a size tag, either a single number or the width and height like `4x3`
+fn parse_size(size: &str) -> Option<(usize, usize)> {
+    let (width, height) = size.split_once('x').unwrap_or((size, size));
+    let (width, height) = (width.parse().ok()?, height.parse().ok()?);
+    if (3..=8).contains(&width) && (3..=8).contains(&height) {
+        Some((width, height))
+    } else {
+        None
+    }
+}
+
+/// Returns whether a token is a move number, like `1.` or `1...`
+fn is_move_number(token: &str) -> bool {
+    let digits = token.trim_end_matches('.');
+    digits.len() < token.len() && !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+impl FromStr for GameRecord {
+    type Err = RecordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().map(str::trim).peekable();
+
+        let (mut size, mut start, mut rules, mut white, mut black, mut result) =
+            (None, None, None, None, None, None);
+        while let Some(line) = lines.next_if(|line| line.is_empty() || line.starts_with('[')) {
+            if line.is_empty() {
+                continue;
+            }
+
+            let (name, value) =
+                parse_tag(line).ok_or_else(|| RecordError::InvalidTag(line.to_string()))?;
+            match name {
+                "Size" => size = Some(value),
+                "Start" => start = Some(value),
+                "Rules" => rules = Some(value),
+                "White" => white = Some(value),
+                "Black" => black = Some(value),
+                "Result" => result = Some(value),
+                // unknown tags are ignored
+                _ => {}
+            }
+        }
+
+        let size = size.ok_or(RecordError::MissingTag("Size"))?;
+        let (width, height) = parse_size(&size).ok_or(RecordError::InvalidSize(size))?;
+        // the start position is optional for games from the usual starting position
+        let start: Board = match start {
+            Some(start) => start.parse().map_err(RecordError::InvalidStart)?,
+            None => Board::with_dimensions(width, height),
+        };
+        // games without a rules tag use standard rules
+        let rules = match rules {
+            Some(rules) => rules.parse().map_err(RecordError::InvalidRules)?,
+            None => Rules::STANDARD,
+        };
+        let start = start.with_rules(rules);
+        if (start.width(), start.height()) != (width, height) {
+            return Err(RecordError::SizeMismatch {
+                size: (width, height),
+                start: (start.width(), start.height()),
+            });
+        }
+
+        let result = result.ok_or(RecordError::MissingTag("Result"))?;
+        let outcome = parse_result(&result).ok_or(RecordError::InvalidResult(result))?;
+
+        let mut record = GameRecord {
+            white: white.ok_or(RecordError::MissingTag("White"))?,
+            black: black.ok_or(RecordError::MissingTag("Black"))?,
+            start: start.clone(),
+            moves: vec![],
+            outcome,
+        };
+
+        let movetext = lines.collect::<Vec<_>>().join(" ");
+        let mut board = start;
+        let mut rest = movetext.trim_start();
+        let mut end = None;
+        while !rest.is_empty() {
+            if end.is_some() {
+                return Err(RecordError::TextAfterResult(rest.to_string()));
+            }
+
+            if let Some(comment) = rest.strip_prefix('{') {
+                let (comment, after) =
+                    split_comment(comment).ok_or(RecordError::UnclosedComment)?;
+                let (time, comment) = parse_comment(&comment)?;
+                let mv = record
+                    .moves
+                    .last_mut()
+                    .ok_or(RecordError::MisplacedComment)?;
+                mv.time = time;
+                mv.comment = comment;
+                rest = after.trim_start();
+                continue;
+            }
+
+            let len = rest
+                .find(|c: char| c.is_whitespace() || c == '{')
+                .unwrap_or(rest.len());
+            let (token, after) = rest.split_at(len);
+            rest = after.trim_start();
+
+            if let Some(result) = parse_result(token) {
+                end = Some(result);
+            } else if !is_move_number(token) {
+                let ply = record.moves.len();
+                if board.is_done() {
+                    return Err(RecordError::MoveAfterEnd(ply));
+                }
+                let mv = board
+                    .parse_move(token)
+                    .map_err(|err| RecordError::InvalidMove { ply, err })?;
+                board.play(mv);
+                record.moves.push(RecordedMove::new(mv));
+            }
+        }
+
+        match end {
+            None => Err(RecordError::MissingResult),
+            Some(end) if end != record.outcome => Err(RecordError::ResultMismatch),
+            Some(_) if record.outcome != board.outcome() => Err(RecordError::WrongResult),
+            Some(_) => Ok(record),
+        }
+    }
+}
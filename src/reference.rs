@@ -0,0 +1,237 @@
+//! A deliberately simple reference implementation of the hexapawn rules, on an array of squares,
+//! to check [`MoveGen`](crate::move_gen::MoveGen) and [`Board`](crate::board::Board) against.
+//! Nothing here uses bitboards, except for reading a [`Board`](crate::board::Board)
+
+use board_game::board::{Outcome, Player};
+use chess::Color;
+
+use crate::bitboard::BitBoardBackend;
+use crate::board::Board;
+use crate::rules::{Rules, StalemateRule};
+
+/// A square as (file, rank), from (0, 0) for a1
+pub type Coord = (usize, usize);
+
+/// A move from one square to another
+pub type RefMove = (Coord, Coord);
+
+/// A hexapawn position as an array of squares
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefBoard {
+    /// The pawn on each square, by rank then file
+    squares: Vec<Vec<Option<Color>>>,
+    /// The side to move
+    side_to_move: Color,
+    /// The square a pawn skipped over with a double step on the last move
+    en_passant: Option<Coord>,
+    /// The rules
+    rules: Rules,
+}
+
+impl RefBoard {
+    /// Reads a `RefBoard` from a [`Board`](Board)
+    pub fn from_board<BB: BitBoardBackend>(board: &Board<BB>) -> Self {
+        let pawn = |file: usize, rank: usize| {
+            let bb = BB::from_index(rank * BB::STRIDE + file);
+            if !(board.pieces(Color::White) & bb).is_empty() {
+                Some(Color::White)
+            } else if !(board.pieces(Color::Black) & bb).is_empty() {
+                Some(Color::Black)
+            } else {
+                None
+            }
+        };
+
+        RefBoard {
+            squares: (0..board.height())
+                .map(|rank| (0..board.width()).map(|file| pawn(file, rank)).collect())
+                .collect(),
+            side_to_move: board.side_to_move(),
+            en_passant: board.en_passant().map(|sq| {
+                let index = BB::index(sq);
+                (index % BB::STRIDE, index / BB::STRIDE)
+            }),
+            rules: board.rules(),
+        }
+    }
+
+    fn width(&self) -> usize {
+        self.squares[0].len()
+    }
+
+    fn height(&self) -> usize {
+        self.squares.len()
+    }
+
+    /// Returns the pawn on a square, or `None` if it's empty or off the board
+    fn get(&self, file: isize, rank: isize) -> Option<Color> {
+        if file < 0 || rank < 0 || file >= self.width() as isize || rank >= self.height() as isize {
+            return None;
+        }
+        self.squares[rank as usize][file as usize]
+    }
+
+    /// Returns whether a square is on the board and empty
+    fn is_empty(&self, file: isize, rank: isize) -> bool {
+        file >= 0
+            && rank >= 0
+            && file < self.width() as isize
+            && rank < self.height() as isize
+            && self.get(file, rank).is_none()
+    }
+
+    /// Returns the direction a color's pawns move in, up or down the ranks
+    fn direction(color: Color) -> isize {
+        match color {
+            Color::White => 1,
+            Color::Black => -1,
+        }
+    }
+
+    /// Returns the legal moves
+    pub fn moves(&self) -> Vec<RefMove> {
+        let color = self.side_to_move;
+        let dir = Self::direction(color);
+        let start_rank = match color {
+            Color::White => 0,
+            Color::Black => self.height() - 1,
+        };
+
+        let mut pushes = vec![];
+        let mut captures = vec![];
+        for rank in 0..self.height() {
+            for file in 0..self.width() {
+                if self.squares[rank][file] != Some(color) {
+                    continue;
+                }
+                let (f, r) = (file as isize, rank as isize);
+                let to = |f: isize, r: isize| ((file, rank), (f as usize, r as usize));
+
+                // one square straight forward to an empty square
+                if self.is_empty(f, r + dir) {
+                    pushes.push(to(f, r + dir));
+                    // two squares from the starting rank with double step
+                    if self.rules.double_step && rank == start_rank && self.is_empty(f, r + 2 * dir)
+                    {
+                        pushes.push(to(f, r + 2 * dir));
+                    }
+                }
+
+                // diagonally forward onto an opposing pawn, or onto the en passant square
+                for df in [-1, 1] {
+                    let (cf, cr) = (f + df, r + dir);
+                    let en_passant = self.en_passant == Some((cf as usize, cr as usize));
+                    if self.get(cf, cr) == Some(!color) || en_passant {
+                        captures.push(to(cf, cr));
+                    }
+                    // or sideways onto an opposing pawn with sideways capture
+                    if self.rules.sideways_capture && self.get(f + df, r) == Some(!color) {
+                        captures.push(to(f + df, r));
+                    }
+                }
+            }
+        }
+
+        if self.rules.forced_capture && !captures.is_empty() {
+            return captures;
+        }
+        pushes.extend(captures);
+        pushes
+    }
+
+    /// Plays a legal move
+    pub fn play(&mut self, ((sf, sr), (df, dr)): RefMove) {
+        let color = self.side_to_move;
+
+        // en passant captures the pawn that skipped over the destination
+        if self.en_passant == Some((df, dr)) && sf != df {
+            let skipped = (dr as isize - Self::direction(color)) as usize;
+            self.squares[skipped][df] = None;
+        }
+
+        self.squares[sr][sf] = None;
+        self.squares[dr][df] = Some(color);
+
+        // a double step can be captured en passant on the next move
+        self.en_passant = if self.rules.en_passant && sr.abs_diff(dr) == 2 {
+            Some((df, (sr + dr) / 2))
+        } else {
+            None
+        };
+        self.side_to_move = !color;
+    }
+
+    /// Returns the amount of pawns of a color
+    fn count(&self, color: Color) -> usize {
+        self.squares
+            .iter()
+            .flatten()
+            .filter(|&&pawn| pawn == Some(color))
+            .count()
+    }
+
+    /// Returns the outcome of the game, or `None` if it's still ongoing
+    pub fn outcome(&self) -> Option<Outcome> {
+        let top = self.height() - 1;
+        let reached = if self.squares[top].contains(&Some(Color::White)) {
+            Some(Player::A)
+        } else if self.squares[0].contains(&Some(Color::Black)) {
+            Some(Player::B)
+        } else {
+            None
+        };
+        let to_move = match self.side_to_move {
+            Color::White => Player::A,
+            Color::Black => Player::B,
+        };
+
+        if let Some(player) = reached {
+            // reaching the far rank wins, or loses in misère
+            return Some(Outcome::WonBy(if self.rules.misere {
+                player.other()
+            } else {
+                player
+            }));
+        }
+        if !self.moves().is_empty() {
+            return None;
+        }
+
+        if self.rules.misere {
+            return Some(Outcome::WonBy(to_move));
+        }
+        Some(match self.rules.stalemate {
+            StalemateRule::Draw => Outcome::Draw,
+            StalemateRule::Loss => Outcome::WonBy(to_move.other()),
+            StalemateRule::Material => {
+                let (white, black) = (self.count(Color::White), self.count(Color::Black));
+                if white > black {
+                    Outcome::WonBy(Player::A)
+                } else if black > white {
+                    Outcome::WonBy(Player::B)
+                } else {
+                    Outcome::Draw
+                }
+            }
+        })
+    }
+
+    /// Returns the amount of leaf nodes `depth` plies from this position, like [`perft`](crate::perft::perft)
+    pub fn perft(&self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        if self.outcome().is_some() {
+            return 0;
+        }
+
+        self.moves()
+            .into_iter()
+            .map(|mv| {
+                let mut board = self.clone();
+                board.play(mv);
+                board.perft(depth - 1)
+            })
+            .sum()
+    }
+}
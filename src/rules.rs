@@ -0,0 +1,129 @@
+//! Rule variants of hexapawn, carried by [`Board`](crate::board::Board)
+
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// The rules a [`Board`](crate::board::Board) is played with. The default is standard hexapawn
+///
+/// In text, the rules are the enabled variants separated by spaces, like `double-step en-passant stalemate-loss`,
+/// or `standard` if none are enabled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rules {
+    /// Pawns on their starting rank can push two squares, if both are empty
+    pub double_step: bool,
+    /// A pawn that just pushed two squares can be captured as if it only pushed one.
+    /// Only has an effect with [`double_step`](Rules::double_step)
+    pub en_passant: bool,
+    /// If any pawn can capture, the side to move must capture
+    pub forced_capture: bool,
+    /// Pawns can also capture an opposing pawn on a square beside them
+    pub sideways_capture: bool,
+    /// Misère (anti-hexapawn): reaching the far rank loses, and having no moves wins
+    pub misere: bool,
+    /// The outcome when the side to move has no moves. Ignored in [misère](Rules::misere)
+    pub stalemate: StalemateRule,
+}
+
+/// The outcome when the side to move has no moves
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum StalemateRule {
+    /// The game is a draw
+    #[default]
+    Draw,
+    /// The side to move loses, like in Gardner's original hexapawn
+    Loss,
+    /// The side with more pawns wins, or it's a draw if both have as many
+    Material,
+}
+
+impl Rules {
+    /// Standard hexapawn, with single pushes and diagonal captures
+    pub const STANDARD: Rules = Rules {
+        double_step: false,
+        en_passant: false,
+        forced_capture: false,
+        sideways_capture: false,
+        misere: false,
+        stalemate: StalemateRule::Draw,
+    };
+
+    /// Returns whether the player who moves can lose by that move: in misère by reaching the far rank
+    /// or leaving the opponent without moves, or with [material](StalemateRule::Material) stalemates
+    /// by leaving the opponent without moves while having fewer pawns
+    pub fn can_lose_after_move(&self) -> bool {
+        self.misere || self.stalemate == StalemateRule::Material
+    }
+
+    /// Returns the names of the variants with whether they're enabled
+    fn variants(&self) -> [(&'static str, bool); 7] {
+        [
+            ("double-step", self.double_step),
+            ("en-passant", self.en_passant),
+            ("forced-capture", self.forced_capture),
+            ("sideways-capture", self.sideways_capture),
+            ("misere", self.misere),
+            ("stalemate-loss", self.stalemate == StalemateRule::Loss),
+            (
+                "stalemate-material",
+                self.stalemate == StalemateRule::Material,
+            ),
+        ]
+    }
+}
+
+impl Display for Rules {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let enabled: Vec<&str> = self
+            .variants()
+            .into_iter()
+            .filter(|&(_, enabled)| enabled)
+            .map(|(name, _)| name)
+            .collect();
+
+        if enabled.is_empty() {
+            f.write_str("standard")
+        } else {
+            f.write_str(&enabled.join(" "))
+        }
+    }
+}
+
+/// An error from parsing [`Rules`](Rules)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RulesError {
+    /// A variant that doesn't exist
+    UnknownVariant(String),
+}
+
+impl Display for RulesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RulesError::UnknownVariant(name) => write!(f, "unknown rule variant '{name}'"),
+        }
+    }
+}
+
+impl Error for RulesError {}
+
+impl FromStr for Rules {
+    type Err = RulesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rules = Rules::STANDARD;
+        for name in s.split_whitespace() {
+            match name {
+                "standard" => {}
+                "double-step" => rules.double_step = true,
+                "en-passant" => rules.en_passant = true,
+                "forced-capture" => rules.forced_capture = true,
+                "sideways-capture" => rules.sideways_capture = true,
+                "misere" => rules.misere = true,
+                "stalemate-loss" => rules.stalemate = StalemateRule::Loss,
+                "stalemate-material" => rules.stalemate = StalemateRule::Material,
+                _ => return Err(RulesError::UnknownVariant(name.to_string())),
+            }
+        }
+        Ok(rules)
+    }
+}
@@ -0,0 +1,249 @@
+//! [`SizedBoard`](SizedBoard), a square hexapawn [`Board`](Board) with its size known at compile time
+//!
+//! The playing area and the ranks to reach are constants for each size, used by move generation,
+//! the outcome and the heuristics, so bots running on a `SizedBoard` are monomorphised for each size in [`SIZES`](crate::SIZES).
+//! [`with_size!`](crate::with_size) picks the `SizedBoard` for a runtime size
+
+use std::error::Error;
+use std::fmt::Display;
+
+use board_game::board::Board as BoardTrait;
+use board_game::board::BoardMoves;
+use board_game::board::BoardSymmetry;
+use board_game::board::Outcome;
+use board_game::board::Player;
+use board_game::symmetry::D1Symmetry;
+use chess::{BitBoard, Color};
+use internal_iterator::Internal;
+use internal_iterator::IteratorExt;
+
+use crate::board::{AllMoves, Board};
+use crate::consts::{BOARD_MASKS, RANKS};
+use crate::move_gen::{Mask, Move, MoveGen};
+use crate::pawn_board::PawnBoard;
+use crate::rules::Rules;
+
+/// A square hexapawn board of size `N`, from 3 to 8
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SizedBoard<const N: usize>(Board);
+
+impl<const N: usize> SizedBoard<N> {
+    /// Fails to compile if `N` isn't 3 to 8
+    const VALID: () = assert!(N >= 3 && N <= 8, "Invalid size, must be 3 to 8");
+
+    /// The playing area
+    pub const MASK: BitBoard = BitBoard(BOARD_MASKS[N - 1]);
+    /// The rank white has to reach to win
+    pub const WHITE_WIN_RANK: BitBoard = BitBoard(RANKS[N - 1]);
+    /// The rank black has to reach to win
+    pub const BLACK_WIN_RANK: BitBoard = BitBoard(RANKS[0]);
+
+    /// Creates a new `SizedBoard` in the starting position
+    pub fn new() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let _ = Self::VALID;
+        SizedBoard(Board::new(N))
+    }
+
+    /// Changes the rules of the board, like [`Board::with_rules`](Board::with_rules)
+    pub fn with_rules(self, rules: Rules) -> Self {
+        SizedBoard(self.0.with_rules(rules))
+    }
+
+    /// Returns the dynamically sized board
+    pub fn board(&self) -> &Board {
+        &self.0
+    }
+}
+
+impl<const N: usize> Default for SizedBoard<N> {
+    fn default() -> Self {
+        SizedBoard::new()
+    }
+}
+
+impl<const N: usize> Display for SizedBoard<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<const N: usize> From<SizedBoard<N>> for Board {
+    fn from(board: SizedBoard<N>) -> Self {
+        board.0
+    }
+}
+
+impl<const N: usize> TryFrom<Board> for SizedBoard<N> {
+    type Error = SizeError;
+
+    fn try_from(board: Board) -> Result<Self, Self::Error> {
+        #[allow(clippy::let_unit_value)]
+        let _ = Self::VALID;
+        if board.width() == N && board.height() == N {
+            Ok(SizedBoard(board))
+        } else {
+            Err(SizeError::WrongSize {
+                expected: N,
+                width: board.width(),
+                height: board.height(),
+            })
+        }
+    }
+}
+
+/// An error from converting a [`Board`](Board) into a [`SizedBoard`](SizedBoard)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SizeError {
+    /// The board isn't `expected` by `expected`
+    WrongSize {
+        expected: usize,
+        width: usize,
+        height: usize,
+    },
+}
+
+impl Display for SizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SizeError::WrongSize {
+                expected,
+                width,
+                height,
+            } => write!(
+                f,
+                "Board is {width}x{height}, expected {expected}x{expected}"
+            ),
+        }
+    }
+}
+
+impl Error for SizeError {}
+
+impl<const N: usize> BoardSymmetry<SizedBoard<N>> for SizedBoard<N> {
+    type Symmetry = D1Symmetry;
+
+    fn map(&self, sym: Self::Symmetry) -> Self {
+        SizedBoard(self.0.map(sym))
+    }
+
+    fn map_move(&self, sym: Self::Symmetry, mv: Move) -> Move {
+        self.0.map_move(sym, mv)
+    }
+}
+
+impl<const N: usize> BoardTrait for SizedBoard<N> {
+    type Move = Move;
+
+    fn next_player(&self) -> Player {
+        self.0.next_player()
+    }
+
+    fn is_available_move(&self, mv: Self::Move) -> bool {
+        self.0.is_available_move(mv)
+    }
+
+    fn play(&mut self, mv: Self::Move) {
+        self.0.play(mv)
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        self.0
+            .outcome_with_ranks(Self::WHITE_WIN_RANK, Self::BLACK_WIN_RANK, Self::MASK)
+    }
+
+    fn can_lose_after_move() -> bool {
+        Board::<BitBoard>::can_lose_after_move()
+    }
+}
+
+impl<const N: usize> PawnBoard for SizedBoard<N> {
+    type BB = BitBoard;
+
+    fn pieces(&self, color: Color) -> BitBoard {
+        self.0.pieces(color)
+    }
+
+    fn side_to_move(&self) -> Color {
+        self.0.side_to_move()
+    }
+
+    fn mask(&self) -> BitBoard {
+        Self::MASK
+    }
+
+    fn move_gen(&self, mask: Mask) -> MoveGen {
+        MoveGen::with_playable_area(&self.0, mask, Self::MASK)
+    }
+
+    fn win_rank(&self, color: Color) -> BitBoard {
+        match color {
+            Color::White => Self::WHITE_WIN_RANK,
+            Color::Black => Self::BLACK_WIN_RANK,
+        }
+    }
+
+    fn capture_squares(&self) -> BitBoard {
+        self.0.capture_squares()
+    }
+
+    fn misere(&self) -> bool {
+        self.0.rules().misere
+    }
+}
+
+impl<'a, const N: usize> BoardMoves<'a, SizedBoard<N>> for SizedBoard<N> {
+    type AllMovesIterator = AllMoves<BitBoard>;
+
+    type AvailableMovesIterator = Internal<MoveGen<BitBoard>>;
+
+    fn all_possible_moves() -> Self::AllMovesIterator {
+        AllMoves::default()
+    }
+
+    fn available_moves(&'a self) -> Self::AvailableMovesIterator {
+        MoveGen::with_playable_area(&self.0, Mask::None, Self::MASK).into_internal()
+    }
+}
+
+/// Runs an expression with a constant for a runtime size from 3 to 8, so it can use [`SizedBoard`](SizedBoard)
+///
+/// ```
+/// use sf21_22::{sized_board::SizedBoard, with_size};
+///
+/// let size = 5;
+/// let board = with_size!(size, N => SizedBoard::<N>::new().to_string());
+/// assert_eq!(board, sf21_22::board::Board::new(size).to_string());
+/// ```
+#[macro_export]
+macro_rules! with_size {
+    ($size:expr, $n:ident => $body:expr) => {
+        match $size {
+            3 => {
+                const $n: usize = 3;
+                $body
+            }
+            4 => {
+                const $n: usize = 4;
+                $body
+            }
+            5 => {
+                const $n: usize = 5;
+                $body
+            }
+            6 => {
+                const $n: usize = 6;
+                $body
+            }
+            7 => {
+                const $n: usize = 7;
+                $body
+            }
+            8 => {
+                const $n: usize = 8;
+                $body
+            }
+            size => panic!("Invalid size {size}, must be 3 to 8"),
+        }
+    };
+}
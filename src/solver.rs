@@ -0,0 +1,245 @@
+//! A strong solver for hexapawn, proving the value of a position with perfect play
+//!
+//! [`Solver`](Solver) searches the whole game tree with alpha-beta, a transposition table
+//! shared between mirrored positions, and [staged move ordering](crate::move_order::StagedMoveGen).
+//! Hexapawn always ends, since every move advances a pawn, so the search needs no depth limit
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use board_game::board::{Board as BoardTrait, BoardSymmetry};
+use board_game::wdl::{OutcomeWDL, POV};
+use chess::BitBoard;
+
+use crate::bitboard::BitBoardBackend;
+use crate::board::Board;
+use crate::move_gen::Move;
+use crate::move_order::StagedMoveGen;
+
+/// The score of winning on the spot. Winning `n` plies from the root scores `WIN - n`
+const WIN: i32 = 1_000_000;
+/// Scores beyond this are wins or losses, not draws
+const DECIDED: i32 = WIN - 10_000;
+/// A score beyond every other score
+const INFINITY: i32 = WIN + 1;
+
+/// The value of a position with perfect play, for the side to move
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution<BB: BitBoardBackend = BitBoard> {
+    /// Whether the side to move wins, draws or loses
+    pub outcome: OutcomeWDL,
+    /// How many plies the game lasts with perfect play.
+    /// The winner wins as quickly as possible and the loser loses as slowly as possible.
+    /// Draws aren't told apart by length, so for a draw it's the length of the principal variation found
+    pub distance: u32,
+    /// A game with perfect play, from the position to the end
+    pub principal_variation: Vec<Move<BB>>,
+}
+
+impl<BB: BitBoardBackend> Solution<BB> {
+    /// Returns the best move, or `None` if the game is already over
+    pub fn best_move(&self) -> Option<Move<BB>> {
+        self.principal_variation.first().copied()
+    }
+}
+
+impl<BB: BitBoardBackend> Display for Solution<BB> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let outcome = match self.outcome {
+            OutcomeWDL::Win => "win",
+            OutcomeWDL::Draw => "draw",
+            OutcomeWDL::Loss => "loss",
+        };
+        write!(f, "{outcome} in {} plies", self.distance)
+    }
+}
+
+/// Whether a score in the transposition table is exact or a bound
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Bound {
+    /// The score is exact
+    Exact,
+    /// The score is at least this
+    Lower,
+    /// The score is at most this
+    Upper,
+}
+
+/// An entry of the transposition table
+#[derive(Debug, Clone, Copy)]
+struct Entry<BB: BitBoardBackend> {
+    /// The score, counting wins and losses from this position instead of from the root
+    score: i32,
+    /// Whether the score is exact or a bound
+    bound: Bound,
+    /// The best move found, in the canonical position
+    best_move: Option<Move<BB>>,
+}
+
+/// A strong solver for hexapawn [`Board`](Board)s
+#[derive(Debug, Clone)]
+pub struct Solver<BB: BitBoardBackend = BitBoard> {
+    /// The transposition table, by canonical position
+    table: HashMap<Board<BB>, Entry<BB>>,
+    /// The amount of positions searched
+    nodes: u64,
+    /// The amount of positions to search before giving up, if any
+    node_limit: Option<u64>,
+}
+
+impl<BB: BitBoardBackend> Default for Solver<BB> {
+    fn default() -> Self {
+        Solver::new()
+    }
+}
+
+impl<BB: BitBoardBackend> Solver<BB> {
+    /// Creates a new `Solver` without a node limit
+    pub fn new() -> Self {
+        Solver {
+            table: HashMap::new(),
+            nodes: 0,
+            node_limit: None,
+        }
+    }
+
+    /// Creates a new `Solver` that gives up after searching `node_limit` positions
+    pub fn with_node_limit(node_limit: u64) -> Self {
+        Solver {
+            node_limit: Some(node_limit),
+            ..Solver::new()
+        }
+    }
+
+    /// Returns the amount of positions searched
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    /// Returns the amount of positions in the transposition table
+    pub fn table_size(&self) -> usize {
+        self.table.len()
+    }
+
+    /// Solves a position, or returns `None` if the node limit was reached first
+    pub fn solve(&mut self, board: &Board<BB>) -> Option<Solution<BB>> {
+        let score = self.search(board, 0, -INFINITY, INFINITY)?;
+
+        // follow moves that keep the score, which counts from the root, until the game ends
+        let mut principal_variation = vec![];
+        let mut board = board.clone();
+        // the score for the side to move
+        let mut target = score;
+        while !board.is_done() {
+            let ply = principal_variation.len() as u32;
+            let mut best = None;
+            for mv in StagedMoveGen::new(&board) {
+                let child = board.clone_and_play(mv);
+                if -self.search(&child, ply + 1, -INFINITY, INFINITY)? == target {
+                    best = Some((mv, child));
+                    break;
+                }
+            }
+            let (mv, child) = best.expect("a move keeps the score");
+            principal_variation.push(mv);
+            board = child;
+            target = -target;
+        }
+
+        let distance = principal_variation.len() as u32;
+        let outcome = match score {
+            0 => OutcomeWDL::Draw,
+            score if score > 0 => OutcomeWDL::Win,
+            _ => OutcomeWDL::Loss,
+        };
+        debug_assert!(outcome == OutcomeWDL::Draw || score.abs() == WIN - distance as i32);
+
+        Some(Solution {
+            outcome,
+            distance,
+            principal_variation,
+        })
+    }
+
+    /// Searches a position `ply` plies from the root with negamax alpha-beta.
+    /// Returns `None` if the node limit was reached
+    fn search(&mut self, board: &Board<BB>, ply: u32, mut alpha: i32, beta: i32) -> Option<i32> {
+        self.nodes += 1;
+        if self.node_limit.is_some_and(|limit| self.nodes > limit) {
+            return None;
+        }
+
+        if let Some(outcome) = board.outcome() {
+            return Some(match outcome.pov(board.next_player()) {
+                OutcomeWDL::Win => WIN - ply as i32,
+                OutcomeWDL::Draw => 0,
+                OutcomeWDL::Loss => -(WIN - ply as i32),
+            });
+        }
+
+        // mirrored positions share an entry
+        let (canonical, sym) = board.canonical();
+        let mut tt_move = None;
+        if let Some(entry) = self.table.get(&canonical) {
+            let score = from_table(entry.score, ply);
+            match entry.bound {
+                Bound::Exact => return Some(score),
+                Bound::Lower if score >= beta => return Some(score),
+                Bound::Upper if score <= alpha => return Some(score),
+                _ => {}
+            }
+            tt_move = entry.best_move.map(|mv| canonical.map_move(sym, mv));
+        }
+
+        // the best move from the table first, then wins, captures and pushes
+        let original_alpha = alpha;
+        let mut best = (-INFINITY, None);
+        let moves = StagedMoveGen::new(board).filter(|&mv| Some(mv) != tt_move);
+        for mv in tt_move.into_iter().chain(moves) {
+            let score = -self.search(&board.clone_and_play(mv), ply + 1, -beta, -alpha)?;
+            if score > best.0 {
+                best = (score, Some(mv));
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let (score, best_move) = best;
+        let bound = if score <= original_alpha {
+            Bound::Upper
+        } else if score >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.insert(
+            canonical,
+            Entry {
+                score: to_table(score, ply),
+                bound,
+                best_move: best_move.map(|mv| board.map_move(sym, mv)),
+            },
+        );
+        Some(score)
+    }
+}
+
+/// Converts a score counting from the root to one counting from the position `ply` plies deep
+fn to_table(score: i32, ply: u32) -> i32 {
+    match score {
+        score if score > DECIDED => score + ply as i32,
+        score if score < -DECIDED => score - ply as i32,
+        score => score,
+    }
+}
+
+/// Converts a score counting from the position `ply` plies deep to one counting from the root
+fn from_table(score: i32, ply: u32) -> i32 {
+    match score {
+        score if score > DECIDED => score - ply as i32,
+        score if score < -DECIDED => score + ply as i32,
+        score => score,
+    }
+}
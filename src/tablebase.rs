@@ -0,0 +1,559 @@
+//! Endgame tablebases for hexapawn by [retrograde analysis](https://www.chessprogramming.org/Retrograde_Analysis)
+//!
+//! [`Tablebase::generate_all`](Tablebase::generate_all) enumerates every legal position of a small size,
+//! and [`Tablebase::generate_reachable`](Tablebase::generate_reachable) finds every position reachable
+//! from a start position, which is all games can reach on sizes too big to enumerate.
+//! Both then work backwards from the positions where the game is over, so every position gets
+//! its value with perfect play and the distance to the result.
+//! Mirrored positions share an entry, and tablebases can be written to and read from files
+
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
+use std::error::Error;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use board_game::board::Board as BoardTrait;
+use board_game::wdl::{OutcomeWDL, POV};
+use chess::Color;
+
+use crate::bitboard::{BitBoard128, BitBoardBackend};
+use crate::board::Board;
+use crate::move_gen::{Move, MoveGen};
+use crate::rules::Rules;
+
+/// The start of every tablebase file
+const MAGIC: &[u8; 4] = b"HXTB";
+/// The version of the file format
+const VERSION: u8 = 1;
+/// The most positions allocated for before reading them from a file
+const MAX_PREALLOCATED: usize = 1 << 20;
+/// The most squares [`Tablebase::generate_all`](Tablebase::generate_all) enumerates the placements of,
+/// 3^12 is about half a million placements. 4x4 boards would already have 43 million
+const MAX_ENUMERATED_SQUARES: usize = 12;
+
+/// The value of a position with perfect play, for the side to move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Value {
+    /// Whether the side to move wins, draws or loses
+    pub outcome: OutcomeWDL,
+    /// How many plies the game lasts with perfect play, or 0 for draws.
+    /// The winner wins as quickly as possible and the loser loses as slowly as possible
+    pub distance: u32,
+}
+
+impl Value {
+    /// The longest distance that fits in a packed value
+    const MAX_DISTANCE: u32 = 63;
+
+    /// Packs the value into a byte, the distance above 2 bits for the outcome
+    fn pack(self) -> u8 {
+        let outcome = match self.outcome {
+            OutcomeWDL::Draw => 0,
+            OutcomeWDL::Win => 1,
+            OutcomeWDL::Loss => 2,
+        };
+        (self.distance as u8) << 2 | outcome
+    }
+
+    /// Unpacks a value packed by [`pack`](Value::pack), or returns `None` if it isn't valid
+    fn unpack(byte: u8) -> Option<Self> {
+        let outcome = match byte & 0b11 {
+            0 => OutcomeWDL::Draw,
+            1 => OutcomeWDL::Win,
+            2 => OutcomeWDL::Loss,
+            _ => return None,
+        };
+        let distance = (byte >> 2) as u32;
+        if outcome == OutcomeWDL::Draw && distance != 0 {
+            return None;
+        }
+        Some(Value { outcome, distance })
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.outcome {
+            OutcomeWDL::Win => write!(f, "win in {} plies", self.distance),
+            OutcomeWDL::Draw => f.write_str("draw"),
+            OutcomeWDL::Loss => write!(f, "loss in {} plies", self.distance),
+        }
+    }
+}
+
+/// An endgame tablebase: the [`Value`](Value) of every legal position of a size,
+/// or of every position reachable from a start position
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tablebase {
+    /// The width of the board
+    width: usize,
+    /// The height of the board
+    height: usize,
+    /// The rules
+    rules: Rules,
+    /// The keys of the canonical positions, sorted
+    keys: Vec<u64>,
+    /// The packed value of each position in `keys`
+    values: Vec<u8>,
+}
+
+impl Tablebase {
+    /// Generates the tablebase of every legal position of a size and rules: every placement
+    /// with at most a rank of pawns for each side that [`Board::from_parts`](Board::from_parts) accepts,
+    /// with either side to move, and the en passant positions reachable from them.
+    ///
+    /// This tries all 3^(width * height) placements, so it's limited to boards of up to 12 squares,
+    /// like 3x3 and 4x3, and returns an error for bigger ones.
+    /// [`generate_reachable`](Tablebase::generate_reachable) is the one to use for playing
+    pub fn generate_all<BB: BitBoardBackend>(
+        width: usize,
+        height: usize,
+        rules: Rules,
+    ) -> Result<Self, TablebaseError> {
+        let squares = width * height;
+        if squares > MAX_ENUMERATED_SQUARES || width > BB::MAX_SIZE || height > BB::MAX_SIZE {
+            return Err(TablebaseError::TooBig { width, height });
+        }
+
+        let mut starts = vec![];
+        for placement in 0..3u64.pow(squares as u32) {
+            // each square as a base 3 digit, like the keys
+            let (mut white, mut black) = (BB::EMPTY, BB::EMPTY);
+            let mut digits = placement;
+            for square in 0..squares {
+                let bb = BB::from_index(square / width * BB::STRIDE + square % width);
+                match digits % 3 {
+                    1 => white |= bb,
+                    2 => black |= bb,
+                    _ => {}
+                }
+                digits /= 3;
+            }
+            if white.pop_count() as usize > width || black.pop_count() as usize > width {
+                continue;
+            }
+
+            for side_to_move in [Color::White, Color::Black] {
+                if let Ok(board) =
+                    Board::from_parts_with_dimensions(width, height, white, black, side_to_move)
+                {
+                    starts.push(board.with_rules(rules));
+                }
+            }
+        }
+        Tablebase::generate_from(width, height, rules, starts)
+    }
+
+    /// Generates the tablebase of every position reachable from `start`, with its size and rules.
+    /// Returns an error if the positions of the size don't fit in the keys
+    pub fn generate_reachable<BB: BitBoardBackend>(
+        start: &Board<BB>,
+    ) -> Result<Self, TablebaseError> {
+        Tablebase::generate_from(
+            start.width(),
+            start.height(),
+            start.rules(),
+            vec![start.clone()],
+        )
+    }
+
+    /// Generates the tablebase of every position reachable from `starts`
+    fn generate_from<BB: BitBoardBackend>(
+        width: usize,
+        height: usize,
+        rules: Rules,
+        starts: Vec<Board<BB>>,
+    ) -> Result<Self, TablebaseError> {
+        let too_big = TablebaseError::TooBig { width, height };
+        if key_space(width, height).is_none() {
+            return Err(too_big);
+        }
+
+        // find every reachable canonical position and its children, indexed in the order they're found
+        let mut index = HashMap::new();
+        let mut keys = vec![];
+        let mut children = vec![];
+        let mut offsets = vec![0];
+        let mut values = vec![];
+        let mut queue = VecDeque::new();
+
+        for start in starts {
+            let canonical = start.canonical().0;
+            let start_key = key(&canonical);
+            if let Entry::Vacant(entry) = index.entry(start_key) {
+                entry.insert(keys.len() as u32);
+                keys.push(start_key);
+                queue.push_back(canonical);
+            }
+        }
+        while let Some(board) = queue.pop_front() {
+            match board.outcome() {
+                Some(outcome) => values.push(Some(Value {
+                    outcome: outcome.pov(board.next_player()),
+                    distance: 0,
+                })),
+                None => {
+                    values.push(None);
+                    for mv in MoveGen::new(&board) {
+                        let child = board.clone_and_play(mv).canonical().0;
+                        let child_key = key(&child);
+                        let child_index = *index.entry(child_key).or_insert_with(|| {
+                            keys.push(child_key);
+                            queue.push_back(child);
+                            keys.len() as u32 - 1
+                        });
+                        children.push(child_index);
+                    }
+                }
+            }
+            offsets.push(children.len());
+        }
+
+        // the parents of each position, from the children
+        let len = keys.len();
+        let mut parent_offsets = vec![0; len + 1];
+        for &child in &children {
+            parent_offsets[child as usize + 1] += 1;
+        }
+        for i in 0..len {
+            parent_offsets[i + 1] += parent_offsets[i];
+        }
+        let mut parents = vec![0; children.len()];
+        let mut next = parent_offsets.clone();
+        for parent in 0..len {
+            for &child in &children[offsets[parent]..offsets[parent + 1]] {
+                parents[next[child as usize]] = parent as u32;
+                next[child as usize] += 1;
+            }
+        }
+
+        // work backwards from the end of the game, nearest first, so a win takes the nearest
+        // lost child and a loss waits for its furthest won child
+        let mut unresolved: Vec<usize> = (0..len).map(|i| offsets[i + 1] - offsets[i]).collect();
+        let mut queue: VecDeque<usize> = (0..len).filter(|&i| values[i].is_some()).collect();
+        while let Some(child) = queue.pop_front() {
+            let value = values[child].unwrap();
+            for &parent in &parents[parent_offsets[child]..parent_offsets[child + 1]] {
+                let parent = parent as usize;
+                if values[parent].is_some() {
+                    continue;
+                }
+                let outcome = match value.outcome {
+                    OutcomeWDL::Loss => OutcomeWDL::Win,
+                    OutcomeWDL::Win => {
+                        unresolved[parent] -= 1;
+                        if unresolved[parent] > 0 {
+                            continue;
+                        }
+                        OutcomeWDL::Loss
+                    }
+                    OutcomeWDL::Draw => continue,
+                };
+                values[parent] = Some(Value {
+                    outcome,
+                    distance: value.distance + 1,
+                });
+                queue.push_back(parent);
+            }
+        }
+
+        // the positions that are left can't be forced either way
+        let draw = Value {
+            outcome: OutcomeWDL::Draw,
+            distance: 0,
+        };
+        let mut entries: Vec<(u64, Value)> = keys
+            .into_iter()
+            .zip(values)
+            .map(|(key, value)| (key, value.unwrap_or(draw)))
+            .collect();
+        if entries
+            .iter()
+            .any(|(_, value)| value.distance > Value::MAX_DISTANCE)
+        {
+            return Err(too_big);
+        }
+        entries.sort_unstable_by_key(|&(key, _)| key);
+
+        Ok(Tablebase {
+            width,
+            height,
+            rules,
+            keys: entries.iter().map(|&(key, _)| key).collect(),
+            values: entries.iter().map(|&(_, value)| value.pack()).collect(),
+        })
+    }
+
+    /// Returns the value of a position, or `None` if it isn't in the tablebase,
+    /// because it has a different size or rules or, if it was generated from a start position,
+    /// can't be reached from it
+    pub fn probe<BB: BitBoardBackend>(&self, board: &Board<BB>) -> Option<Value> {
+        if (board.width(), board.height(), board.rules()) != (self.width, self.height, self.rules) {
+            return None;
+        }
+        let i = self.keys.binary_search(&key(&board.canonical().0)).ok()?;
+        Value::unpack(self.values[i])
+    }
+
+    /// Returns the moves with the best value: the fastest win, otherwise a draw, otherwise the slowest loss.
+    /// Returns `None` if the position isn't in the tablebase or the game is over
+    pub fn best_moves<BB: BitBoardBackend>(&self, board: &Board<BB>) -> Option<Vec<Move<BB>>> {
+        if board.is_done() {
+            return None;
+        }
+        self.probe(board)?;
+
+        // higher is better for the side to move
+        let rank = |value: Value| match value.outcome {
+            OutcomeWDL::Loss => (2, -(value.distance as i32)),
+            OutcomeWDL::Draw => (1, 0),
+            OutcomeWDL::Win => (0, value.distance as i32),
+        };
+        let mut best = None;
+        let mut moves = vec![];
+        for mv in MoveGen::new(board) {
+            // the value of the position after the move, for the opponent
+            let rank = rank(self.probe(&board.clone_and_play(mv))?);
+            if best.is_none_or(|best| rank > best) {
+                best = Some(rank);
+                moves.clear();
+            }
+            if best == Some(rank) {
+                moves.push(mv);
+            }
+        }
+        (!moves.is_empty()).then_some(moves)
+    }
+
+    /// Returns the width of the board
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of the board
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the rules
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    /// Returns the amount of positions, counting mirrored positions once
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns whether there are no positions
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Writes the tablebase in its file format: the magic bytes `HXTB`, the version,
+    /// the width, the height, the length and text of the rules, the amount of positions
+    /// as a little endian `u64`, the differences between the sorted keys as
+    /// [LEB128](https://en.wikipedia.org/wiki/LEB128), then a byte for each value
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let rules = self.rules.to_string();
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[
+            VERSION,
+            self.width as u8,
+            self.height as u8,
+            rules.len() as u8,
+        ])?;
+        writer.write_all(rules.as_bytes())?;
+        writer.write_all(&(self.keys.len() as u64).to_le_bytes())?;
+
+        let mut previous = 0;
+        for &key in &self.keys {
+            let mut delta = key - previous;
+            previous = key;
+            loop {
+                let byte = (delta & 0x7f) as u8;
+                delta >>= 7;
+                if delta == 0 {
+                    writer.write_all(&[byte])?;
+                    break;
+                }
+                writer.write_all(&[byte | 0x80])?;
+            }
+        }
+        writer.write_all(&self.values)?;
+        writer.flush()
+    }
+
+    /// Reads a tablebase written by [`write`](Tablebase::write)
+    pub fn read<R: Read>(mut reader: R) -> Result<Self, TablebaseError> {
+        let invalid = |msg: &str| TablebaseError::InvalidFormat(msg.to_string());
+        let mut read_bytes = |len: usize| -> io::Result<Vec<u8>> {
+            let mut bytes = vec![0; len];
+            reader.read_exact(&mut bytes)?;
+            Ok(bytes)
+        };
+
+        if read_bytes(4)? != MAGIC {
+            return Err(invalid("not a tablebase"));
+        }
+        let header = read_bytes(4)?;
+        if header[0] != VERSION {
+            return Err(TablebaseError::InvalidFormat(format!(
+                "unsupported version {}",
+                header[0]
+            )));
+        }
+        let (width, height) = (header[1] as usize, header[2] as usize);
+        // no bitboard backend has boards bigger than the 128 bit one
+        let size_range = 3..=BitBoard128::MAX_SIZE;
+        let key_space = key_space(width, height)
+            .filter(|_| size_range.contains(&width) && size_range.contains(&height))
+            .ok_or_else(|| {
+                TablebaseError::InvalidFormat(format!("invalid size {width}x{height}"))
+            })?;
+        let rules = String::from_utf8(read_bytes(header[3] as usize)?)
+            .map_err(|_| invalid("invalid rules"))?
+            .parse()
+            .map_err(|err| TablebaseError::InvalidFormat(format!("{err}")))?;
+        let len = u64::from_le_bytes(read_bytes(8)?.try_into().unwrap());
+        // every key is different, so there can't be more positions than keys
+        if len > key_space {
+            return Err(invalid("too many positions"));
+        }
+        let len = len as usize;
+
+        // a corrupt length fails when the file runs out instead of when allocating
+        let mut keys = Vec::with_capacity(len.min(MAX_PREALLOCATED));
+        let mut previous = 0u64;
+        for i in 0..len {
+            let mut delta = 0u64;
+            for shift in (0..64).step_by(7) {
+                let byte = read_bytes(1)?[0];
+                let bits = (byte & 0x7f) as u64;
+                // the last byte only has room for the top bit
+                if (bits << shift) >> shift != bits {
+                    return Err(invalid("key out of range"));
+                }
+                delta |= bits << shift;
+                if byte & 0x80 == 0 {
+                    break;
+                }
+                if shift == 63 {
+                    return Err(invalid("key too long"));
+                }
+            }
+            // the keys are strictly increasing
+            if i > 0 && delta == 0 {
+                return Err(invalid("keys aren't sorted"));
+            }
+            previous = previous
+                .checked_add(delta)
+                .filter(|&key| key < key_space)
+                .ok_or_else(|| invalid("key out of range"))?;
+            keys.push(previous);
+        }
+
+        let mut values = Vec::with_capacity(len.min(MAX_PREALLOCATED));
+        reader.by_ref().take(len as u64).read_to_end(&mut values)?;
+        if values.len() != len {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        if values.iter().any(|&value| Value::unpack(value).is_none()) {
+            return Err(invalid("invalid value"));
+        }
+
+        Ok(Tablebase {
+            width,
+            height,
+            rules,
+            keys,
+            values,
+        })
+    }
+
+    /// Writes the tablebase to a file
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.write(BufWriter::new(File::create(path)?))
+    }
+
+    /// Reads a tablebase from a file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TablebaseError> {
+        Tablebase::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Returns the default file path for the tablebase of a size and rules,
+    /// in [`tablebases_path`](crate::tablebases_path)
+    pub fn path(width: usize, height: usize, rules: Rules) -> PathBuf {
+        let rules = rules.to_string().replace(' ', "_");
+        crate::tablebases_path().join(format!("{width}x{height}_{rules}.hxtb"))
+    }
+}
+
+/// Returns the amount of possible keys of a size, or `None` if it doesn't fit in a `u64`
+fn key_space(width: usize, height: usize) -> Option<u64> {
+    3u64.checked_pow((width * height) as u32)?
+        .checked_mul(2)?
+        .checked_mul(width as u64 + 1)
+}
+
+/// Returns the key of a position: each square as a base 3 digit, the side to move,
+/// then the file of the en passant square, if any
+fn key<BB: BitBoardBackend>(board: &Board<BB>) -> u64 {
+    let (white, black) = (board.pieces(Color::White), board.pieces(Color::Black));
+    let mut key = 0;
+    for rank in 0..board.height() {
+        for file in 0..board.width() {
+            let bb = BB::from_index(rank * BB::STRIDE + file);
+            let square = if !(white & bb).is_empty() {
+                1
+            } else if !(black & bb).is_empty() {
+                2
+            } else {
+                0
+            };
+            key = key * 3 + square;
+        }
+    }
+
+    let side_to_move = match board.side_to_move() {
+        Color::White => 0,
+        Color::Black => 1,
+    };
+    let en_passant = board
+        .en_passant()
+        .map_or(0, |sq| BB::index(sq) % BB::STRIDE + 1);
+    (key * 2 + side_to_move) * (board.width() as u64 + 1) + en_passant as u64
+}
+
+/// An error from generating, reading or writing a [`Tablebase`](Tablebase)
+#[derive(Debug)]
+pub enum TablebaseError {
+    /// The positions of a size don't fit in a tablebase
+    TooBig { width: usize, height: usize },
+    /// Reading or writing the file failed
+    Io(io::Error),
+    /// The file isn't a valid tablebase
+    InvalidFormat(String),
+}
+
+impl Display for TablebaseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TablebaseError::TooBig { width, height } => {
+                write!(f, "{width}x{height} is too big for a tablebase")
+            }
+            TablebaseError::Io(err) => write!(f, "{err}"),
+            TablebaseError::InvalidFormat(msg) => write!(f, "invalid tablebase: {msg}"),
+        }
+    }
+}
+
+impl Error for TablebaseError {}
+
+impl From<io::Error> for TablebaseError {
+    fn from(err: io::Error) -> Self {
+        TablebaseError::Io(err)
+    }
+}
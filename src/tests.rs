@@ -1,67 +1,66 @@
-use std::{collections::HashMap, hash::Hash};
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
 
+use super::bitboard::*;
 use super::board::*;
+use super::bot::alpha_beta_bot::AlphaBetaBot;
+use super::bot::heuristic::{
+    advancement_eval, material_eval, AdvancementHeuristic, AlwaysCaptureBot, AlwaysPushBot,
+    MaterialHeuristic, SolverHeuristicSimplified,
+};
+use super::bot::mcts_heuristic_bot::MCTSHeuristicBot;
+use super::bot::tablebase_bot::TablebaseBot;
+use super::clock::{Clock, TimeControl};
+use super::consts::{board_mask, BOARD_MASKS};
+use super::history::*;
 use super::move_gen::*;
+use super::pawn_board::PawnBoard;
+use super::perft::{divide, perft, perft_make_unmake, REFERENCE};
+use super::record::*;
+use super::reference::{RefBoard, RefMove};
+use super::rules::*;
+use super::solver::Solver;
+use super::tablebase::{Tablebase, TablebaseError};
+use super::SIZES;
 
+use board_game::ai::mcts::MCTSBot;
+use board_game::ai::minimax::{Heuristic, MiniMaxBot};
+use board_game::ai::simple::RandomBot;
+use board_game::ai::Bot;
 use board_game::board::Board as BoardTrait;
 use board_game::board::BoardMoves;
+use board_game::board::BoardSymmetry;
 use board_game::board::Outcome;
 use board_game::board::Player;
-use chess::Square;
+use board_game::symmetry::{D1Symmetry, Symmetry};
+use board_game::util::bot_game::run;
+use board_game::wdl::{Flip, OutcomeWDL, POV};
+use chess::{BitBoard, Color, Square};
 use internal_iterator::InternalIterator;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
-// tweaked perft from board-game
-pub fn perft<B: BoardTrait>(board: &B, depth: u32) -> u64 {
-    fn perft_recurse<B: BoardTrait + Hash>(
-        map: &mut HashMap<(B, u32), u64>,
-        board: B,
-        depth: u32,
-    ) -> u64 {
-        println!("{board}");
-        if depth == 0 {
-            return 1;
-        }
-        if board.is_done() {
-            return 0;
-        }
-        if depth == 1 {
-            return board
-                .available_moves()
-                .inspect(|mv| println!("{mv}"))
-                .count() as u64;
-        }
-
-        // we need keys (B, depth) because otherwise we risk miscounting if the same board is encountered at different depths
-        let key = (board, depth);
-        let board = &key.0;
-
-        if let Some(&p) = map.get(&key) {
-            return p;
-        }
-
-        let mut p = 0;
-        board.available_moves().for_each(|mv: B::Move| {
-            let new_board = board.clone_and_play(mv);
-            println!("{mv}");
-            println!("{new_board}");
-            p += perft_recurse(map, new_board, depth - 1);
-        });
-
-        map.insert(key, p);
-        p
+/// Collects every position reachable from `board` into `seen`
+pub fn reachable(board: &Board, seen: &mut HashSet<Board>) {
+    if !seen.insert(board.clone()) || board.is_done() {
+        return;
     }
-    let mut map = HashMap::default();
-    perft_recurse(&mut map, board.clone(), depth)
+    board
+        .available_moves()
+        .for_each(|mv| reachable(&board.clone_and_play(mv), seen));
 }
 
-pub fn inspect_moves(board: &Board) {
-    if board
-        .available_moves()
-        .inspect(|mv| println!("{mv}"))
-        .count()
-        == 0
-    {
-        println!("No moves");
+/// Plays seeded random games on `size`, calling `f` on every position
+pub fn random_games(size: usize, games: u64, mut f: impl FnMut(&Board)) {
+    let mut rng = StdRng::seed_from_u64(size as u64);
+    for _ in 0..games {
+        let mut board = Board::new(size);
+        f(&board);
+        while !board.is_done() {
+            board.play(board.random_available_move(&mut rng));
+            f(&board);
+        }
     }
 }
 
@@ -104,7 +103,1811 @@ fn outcome() {
     board_draw.play(Move::new(Square::A1, Square::A2));
     board_draw.play(Move::new(Square::B3, Square::B2));
     board_draw.play(Move::new(Square::C1, Square::C2));
-    inspect_moves(&board_draw);
     println!("Draw:\n{board_draw}");
     assert_eq!(board_draw.outcome(), Some(Outcome::Draw));
 }
+
+#[test]
+fn notation() {
+    assert_eq!(Board::new(3).to_notation(), "ppp/3/PPP w");
+    assert_eq!(
+        Board::new(8).to_notation(),
+        "pppppppp/8/8/8/8/8/8/PPPPPPPP w"
+    );
+
+    let mut board = Board::new(3);
+    board.play(Move::new(Square::A1, Square::A2));
+    assert_eq!(board.to_notation(), "ppp/P2/1PP b");
+    assert_eq!("ppp/P2/1PP b".parse(), Ok(board));
+
+    let board: Board = "1p1/p1P/P2 w".parse().unwrap();
+    assert_eq!(board.pieces(Color::White), BitBoard(0x0000000000000401));
+    assert_eq!(board.pieces(Color::Black), BitBoard(0x0000000000020100));
+    assert_eq!(board.side_to_move(), Color::White);
+    assert_eq!((board.width(), board.height()), (3, 3));
+}
+
+#[test]
+fn notation_errors() {
+    assert_eq!(
+        "ppp/3/PPP".parse::<Board>(),
+        Err(ParseBoardError::WrongFieldCount(1))
+    );
+    assert_eq!(
+        "pp/PP w".parse::<Board>(),
+        Err(ParseBoardError::InvalidSize(2))
+    );
+    assert_eq!(
+        "ppp/99999999999999999999/PPP w".parse::<Board>(),
+        Err(ParseBoardError::WrongRankLength { rank: 2, len: 9 })
+    );
+    assert_eq!(
+        "ppp/3/PKP w".parse::<Board>(),
+        Err(ParseBoardError::InvalidPiece('K'))
+    );
+    assert_eq!(
+        "ppp/4/PPP w".parse::<Board>(),
+        Err(ParseBoardError::WrongRankLength { rank: 2, len: 4 })
+    );
+    assert_eq!(
+        "ppp/3/PP w".parse::<Board>(),
+        Err(ParseBoardError::WrongRankLength { rank: 1, len: 2 })
+    );
+    assert_eq!(
+        "ppp/3/PPP x".parse::<Board>(),
+        Err(ParseBoardError::InvalidSideToMove("x".to_string()))
+    );
+    assert_eq!(
+        "P2/3/1p1 w".parse::<Board>(),
+        Err(ParseBoardError::InvalidPosition(BoardError::AlreadyWon(
+            Color::White
+        )))
+    );
+}
+
+#[test]
+fn from_parts() {
+    let start = Board::new(4);
+    assert_eq!(
+        Board::from_parts(
+            4,
+            start.pieces(Color::White),
+            start.pieces(Color::Black),
+            Color::White
+        ),
+        Ok(start)
+    );
+
+    assert_eq!(
+        Board::from_parts(9, BitBoard(0), BitBoard(0), Color::White),
+        Err(BoardError::InvalidSize(9))
+    );
+    assert_eq!(
+        Board::from_parts(3, BitBoard(0x103), BitBoard(0x10100), Color::White),
+        Err(BoardError::Overlap(BitBoard(0x100)))
+    );
+    assert_eq!(
+        Board::from_parts(3, BitBoard(0x0B), BitBoard(0x70000), Color::White),
+        Err(BoardError::OffBoard {
+            color: Color::White,
+            pawns: BitBoard(0x08)
+        })
+    );
+    assert_eq!(
+        Board::from_parts(3, BitBoard(0x07), BitBoard(0x1000000), Color::White),
+        Err(BoardError::OffBoard {
+            color: Color::Black,
+            pawns: BitBoard(0x1000000)
+        })
+    );
+    // white won last move, so it can't be white's move
+    assert_eq!(
+        Board::from_parts(3, BitBoard(0x10003), BitBoard(0x60000), Color::White),
+        Err(BoardError::AlreadyWon(Color::White))
+    );
+    assert_eq!(
+        Board::from_parts(3, BitBoard(0x06), BitBoard(0x60001), Color::Black),
+        Err(BoardError::AlreadyWon(Color::Black))
+    );
+    assert!(Board::from_parts(3, BitBoard(0x10003), BitBoard(0x60000), Color::Black).is_ok());
+}
+
+#[test]
+fn notation_round_trip() {
+    // small sizes are checked exhaustively, larger sizes through random games
+    for size in [3, 4] {
+        let mut seen = HashSet::new();
+        reachable(&Board::new(size), &mut seen);
+        for board in seen {
+            assert_eq!(board.to_notation().parse(), Ok(board));
+        }
+    }
+
+    for size in SIZES {
+        random_games(size, 100, |board| {
+            assert_eq!(board.to_notation().parse().as_ref(), Ok(board));
+        });
+    }
+}
+
+#[test]
+fn parse_move() {
+    assert_eq!("a1a2".parse(), Ok(Move::new(Square::A1, Square::A2)));
+    assert_eq!("h7g8".parse(), Ok(Move::new(Square::H7, Square::G8)));
+    for s in ["", "a1", "a1a2a3", "a1 a2", "i1a2", "a1a9", "a0a1"] {
+        assert_eq!(s.parse::<Move>(), Err(MoveError::Syntax(s.to_string())));
+    }
+
+    let mut board = Board::new(3);
+    assert_eq!(
+        board.parse_move("b1b2"),
+        Ok(Move::new(Square::B1, Square::B2))
+    );
+    assert_eq!(
+        board.parse_move("b1"),
+        Err(MoveError::Syntax("b1".to_string()))
+    );
+    assert_eq!(
+        board.parse_move("c1d2"),
+        Err(MoveError::OffBoard(Square::D2))
+    );
+    assert_eq!(
+        board.parse_move("a1b2"),
+        Err(MoveError::Illegal(Move::new(Square::A1, Square::B2)))
+    );
+    assert_eq!(
+        board.parse_move("a3a2"),
+        Err(MoveError::Illegal(Move::new(Square::A3, Square::A2)))
+    );
+
+    // every generated move survives a round trip through text
+    for mv in ["a1a2", "b3a2", "b1a2"] {
+        let parsed = board.parse_move(mv).unwrap();
+        assert_eq!(parsed.to_string(), mv);
+        board.play(parsed);
+    }
+}
+
+#[test]
+fn game_record() {
+    let text = r#"[Size "3"]
+[Start "ppp/3/PPP w"]
+[White "White \"W\""]
+[Black "Black"]
+[Result "0-1"]
+
+1. a1a2 {[%emt 0.5] first move} b3a2 {pawn takes} 2. c1c2 {[%emt 0.25]} a2b1 0-1
+"#;
+    let record: GameRecord = text.parse().unwrap();
+    assert_eq!(record.dimensions(), (3, 3));
+    assert_eq!(record.white, "White \"W\"");
+    assert_eq!(record.outcome, Some(Outcome::WonBy(Player::B)));
+    assert_eq!(
+        record.moves[0],
+        RecordedMove {
+            mv: Move::new(Square::A1, Square::A2),
+            time: Some(0.5),
+            comment: Some("first move".to_string())
+        }
+    );
+    assert_eq!(record.moves[1].comment.as_deref(), Some("pawn takes"));
+    assert_eq!(record.moves[2].time, Some(0.25));
+    assert_eq!(record.moves[3].mv, Move::new(Square::A2, Square::B1));
+    assert_eq!(record.to_string(), text);
+
+    // black to move first
+    let mut record = GameRecord::new("ppp/P2/1PP b".parse().unwrap(), "A", "B");
+    record
+        .moves
+        .push(RecordedMove::new(Move::new(Square::B3, Square::A2)));
+    record
+        .moves
+        .push(RecordedMove::new(Move::new(Square::B1, Square::A2)));
+    assert!(record.to_string().contains("\n1... b3a2 2. b1a2 *\n"));
+    assert_eq!(record.to_string().parse(), Ok(record.clone()));
+
+    // comments with braces and backslashes are escaped
+    record.moves[0].comment = Some("a {brace} and a \\".to_string());
+    record.moves[1].time = Some(0.5);
+    record.moves[1].comment = Some("}".to_string());
+    assert!(record
+        .to_string()
+        .contains("1... b3a2 {a {brace\\} and a \\\\} 2. b1a2 {[%emt 0.5] \\}} *"));
+    assert_eq!(record.to_string().parse(), Ok(record));
+}
+
+#[test]
+fn game_record_archive() {
+    // the tournament writes every game with its move times, followed by a blank line
+    let (result, times) = run_timed(
+        || Board::new(4),
+        || RandomBot::new(StdRng::seed_from_u64(0)),
+        || AlwaysPushBot::new(StdRng::seed_from_u64(1)),
+        3,
+        true,
+    );
+    assert_eq!(result.replays.len(), 6);
+    let mut archive = String::new();
+    let mut expected = vec![];
+    for (replay, times) in result.replays.iter().zip(&times) {
+        assert_eq!(times.len(), replay.moves.len());
+        let record = GameRecord::from(replay).with_times(times);
+        assert!(record.moves.iter().all(|mv| mv.time.is_some()));
+        archive.push_str(&format!("{record}\n"));
+        expected.push(record);
+    }
+
+    assert_eq!(GameRecord::parse_all(&archive), Ok(expected));
+    assert_eq!(GameRecord::parse_all(""), Ok(vec![]));
+
+    // an invalid record is reported
+    let archive = archive.replacen("[Size \"4\"]", "[Size \"9\"]", 1);
+    assert_eq!(
+        GameRecord::parse_all(&archive),
+        Err(RecordError::InvalidSize("9".to_string()))
+    );
+}
+
+#[test]
+fn game_record_errors() {
+    let record = |tags: &str, moves: &str| format!("{tags}\n\n{moves}").parse::<GameRecord>();
+    let tags = "[Size \"3\"]\n[White \"A\"]\n[Black \"B\"]\n[Result \"*\"]";
+
+    assert!(record(tags, "1. a1a2 *").is_ok());
+    assert_eq!(
+        record("[Size \"3\"]\n[White \"A\"]\n[Result \"*\"]", "*"),
+        Err(RecordError::MissingTag("Black"))
+    );
+    assert_eq!(
+        record("[Size 3]", "*"),
+        Err(RecordError::InvalidTag("[Size 3]".to_string()))
+    );
+    assert_eq!(
+        record(&format!("{tags}\n[Start \"pppp/4/4/PPPP w\"]"), "*"),
+        Err(RecordError::SizeMismatch {
+            size: (3, 3),
+            start: (4, 4)
+        })
+    );
+    assert_eq!(
+        record(tags, "1. a1a3 *"),
+        Err(RecordError::InvalidMove {
+            ply: 0,
+            err: MoveError::Illegal(Move::new(Square::A1, Square::A3))
+        })
+    );
+    assert_eq!(
+        record(tags, "{hi} 1. a1a2 *"),
+        Err(RecordError::MisplacedComment)
+    );
+    assert_eq!(
+        record(tags, "1. a1a2 {hi *"),
+        Err(RecordError::UnclosedComment)
+    );
+    assert_eq!(
+        record(tags, "1. a1a2 {[%emt x]} *"),
+        Err(RecordError::InvalidTime("x".to_string()))
+    );
+    assert_eq!(record(tags, "1. a1a2"), Err(RecordError::MissingResult));
+    assert_eq!(
+        record(tags, "1. a1a2 * b3a2"),
+        Err(RecordError::TextAfterResult("b3a2".to_string()))
+    );
+    assert_eq!(
+        record(tags, "1. a1a2 0-1"),
+        Err(RecordError::ResultMismatch)
+    );
+    assert_eq!(
+        record(&tags.replace('*', "1-0"), "1. a1a2 b3a2 2. c1c2 a2b1 1-0"),
+        Err(RecordError::WrongResult)
+    );
+    assert_eq!(
+        record(
+            &tags.replace('*', "0-1"),
+            "1. a1a2 b3a2 2. c1c2 a2b1 3. c2c3 0-1"
+        ),
+        Err(RecordError::MoveAfterEnd(4))
+    );
+}
+
+#[test]
+fn game_record_play() {
+    let mut rng = StdRng::seed_from_u64(0);
+    for size in SIZES {
+        let record = play_game(
+            &Board::new(size),
+            &mut RandomBot::new(&mut rng.clone()),
+            &mut RandomBot::new(&mut rng),
+        );
+        assert!(record.board().is_done());
+        assert!(record.moves.iter().all(|mv| mv.time.is_some()));
+        assert_eq!(record.to_string().parse(), Ok(record));
+    }
+}
+
+#[test]
+fn make_unmake() {
+    let mut board = Board::new(3);
+    assert_eq!(perft_make_unmake(&mut board, 1), 3);
+    assert_eq!(perft_make_unmake(&mut board, 2), 10);
+    assert_eq!(perft_make_unmake(&mut board, 3), 28);
+    assert_eq!(board, Board::new(3));
+
+    let mut rng = StdRng::seed_from_u64(0);
+    for size in SIZES {
+        let mut board = Board::new(size);
+        while !board.is_done() {
+            // every move can be undone back to the same position
+            board.available_moves().for_each(|mv| {
+                let before = board.clone();
+                let undo = board.make(mv);
+                assert_eq!(board, before.clone_and_play(mv));
+                board.unmake(mv, undo);
+                assert_eq!(board, before);
+            });
+            board.play(board.random_available_move(&mut rng));
+        }
+    }
+}
+
+#[test]
+fn game_history() {
+    let mut history = GameHistory::new(Board::new(3));
+    assert!(history.takeback().is_none());
+
+    let moves = [
+        Move::new(Square::A1, Square::A2),
+        Move::new(Square::B3, Square::A2),
+        Move::new(Square::B1, Square::A2),
+    ];
+    for mv in moves {
+        history.play(mv);
+    }
+    assert_eq!(history.len(), 3);
+    assert!(history.moves().eq(moves));
+    assert_eq!(history.board().to_notation(), "p1p/P2/2P b");
+    assert_eq!(history.start(), Board::new(3));
+
+    // taking back the capture restores black's pawn
+    assert_eq!(history.takeback(), Some(moves[2]));
+    assert_eq!(history.board().to_notation(), "p1p/p2/1PP w");
+
+    history.takeback_to(0);
+    assert!(history.is_empty());
+    assert_eq!(history.board(), &Board::new(3));
+}
+
+#[test]
+fn zobrist() {
+    assert_ne!(Board::new(3).zobrist(), Board::new(4).zobrist());
+    let black_to_move: Board = "ppp/3/PPP b".parse().unwrap();
+    assert_ne!(black_to_move.zobrist(), Board::new(3).zobrist());
+
+    for size in SIZES {
+        random_games(size, 100, |board| {
+            assert_eq!(board.zobrist(), board.compute_zobrist());
+        });
+    }
+
+    // unmake restores the key
+    let mut board = Board::new(4);
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut history = vec![];
+    while !board.is_done() {
+        let mv = board.random_available_move(&mut rng);
+        history.push((mv, board.make(mv)));
+        assert_eq!(board.zobrist(), board.compute_zobrist());
+    }
+    for (mv, undo) in history.into_iter().rev() {
+        board.unmake(mv, undo);
+        assert_eq!(board.zobrist(), board.compute_zobrist());
+    }
+    assert_eq!(board, Board::new(4));
+}
+
+#[test]
+fn mirror_symmetry() {
+    let mirror = D1Symmetry::new(true);
+    let board: Board = "p1p/1P1/P2 b".parse().unwrap();
+    assert_eq!(board.map(mirror).to_notation(), "p1p/1P1/2P b");
+    assert_eq!(
+        board.map_move(mirror, Move::new(Square::A3, Square::B2)),
+        Move::new(Square::C3, Square::B2)
+    );
+    let board: Board = "pp3/5/1P3/5/P3P w".parse().unwrap();
+    assert_eq!(board.map(mirror).to_notation(), "3pp/5/3P1/5/P3P w");
+
+    for size in SIZES {
+        random_games(size, 20, |board| {
+            let mirrored = board.map(mirror);
+            assert_eq!(mirrored.map(mirror), *board);
+            assert_eq!(mirrored.zobrist(), mirrored.compute_zobrist());
+            assert_eq!(mirrored.outcome(), board.outcome());
+            if board.is_done() {
+                return;
+            }
+
+            // the moves of the mirrored position are the mirrored moves
+            let mut moves: Vec<Move> = board
+                .available_moves()
+                .map(|mv| board.map_move(mirror, mv))
+                .collect();
+            let mut mirrored_moves: Vec<Move> = mirrored.available_moves().collect();
+            moves.sort();
+            mirrored_moves.sort();
+            assert_eq!(moves, mirrored_moves);
+
+            // both share a canonical position
+            for sym in D1Symmetry::all() {
+                let (canonical, to_canonical) = board.map(*sym).canonical();
+                assert_eq!(canonical, board.canonical().0);
+                assert_eq!(board.map(*sym).map(to_canonical), canonical);
+            }
+        });
+    }
+}
+
+#[test]
+fn rectangular() {
+    let board = Board::with_dimensions(3, 6);
+    assert_eq!(board.to_notation(), "ppp/3/3/3/3/PPP w");
+    assert_eq!(board.to_string().lines().count(), 6 + 3);
+    assert_eq!(board.to_string().lines().nth(6), Some("---*"));
+    let board = Board::with_dimensions(6, 3);
+    assert_eq!(board.to_notation(), "pppppp/6/PPPPPP w");
+    assert_eq!(board.to_string().lines().nth(4), Some("abcdef"));
+    assert_eq!(board.mask(), BitBoard(0x3F3F3F));
+    // black's pawns count the squares up to the top of the board
+    let board = Board::with_dimensions(3, 4);
+    assert_eq!(
+        advancement_eval(board.pieces(Color::Black), Color::Black, board.mask()),
+        3
+    );
+
+    assert_eq!(
+        "ppp/3/3/3/3/PPP w".parse(),
+        Ok(Board::with_dimensions(3, 6))
+    );
+    assert_eq!(
+        "pppp/4/PPP w".parse::<Board>(),
+        Err(ParseBoardError::WrongRankLength { rank: 1, len: 3 })
+    );
+    assert_eq!(
+        "pp/2/PP w".parse::<Board>(),
+        Err(ParseBoardError::InvalidSize(2))
+    );
+    assert_eq!(
+        Board::from_parts_with_dimensions(3, 4, BitBoard(0x07), BitBoard(0xF000000), Color::White),
+        Err(BoardError::OffBoard {
+            color: Color::Black,
+            pawns: BitBoard(0x8000000)
+        })
+    );
+
+    // white wins on the 4th rank of a 3x4 board
+    let mut board = Board::with_dimensions(3, 4);
+    for mv in ["a1a2", "c4c3", "a2a3", "c3c2", "a3b4"] {
+        assert_eq!(board.outcome(), None);
+        board.play(board.parse_move(mv).unwrap());
+    }
+    assert_eq!(board.outcome(), Some(Outcome::WonBy(Player::A)));
+    // and can't move off the side of a 3 wide board
+    assert_eq!(
+        Board::with_dimensions(3, 4).parse_move("c1d2"),
+        Err(MoveError::OffBoard(Square::D2))
+    );
+
+    let mirror = D1Symmetry::new(true);
+    let mut rng = StdRng::seed_from_u64(0);
+    for (width, height) in [(3, 6), (6, 3), (4, 8), (8, 5)] {
+        let mut board = Board::with_dimensions(width, height);
+        let mut record = GameRecord::new(board.clone(), "A", "B");
+        while !board.is_done() {
+            assert_eq!(board.to_notation().parse().as_ref(), Ok(&board));
+            assert_eq!(board.map(mirror).map(mirror), board);
+            assert_eq!(board.occupied() & !board.mask(), BitBoard(0));
+            let mv = board.random_available_move(&mut rng);
+            record.moves.push(RecordedMove::new(mv));
+            board.play(mv);
+        }
+        record.outcome = board.outcome();
+        assert_eq!(record.to_string().parse(), Ok(record));
+    }
+}
+
+#[test]
+fn moves_on_board() {
+    for size in SIZES {
+        assert_eq!(board_mask(size, size), BOARD_MASKS[size - 1]);
+    }
+
+    let on_board = |board: &Board| {
+        for mask in [Mask::None, Mask::Capture, Mask::Push] {
+            for mv in MoveGen::with_mask(board, mask) {
+                let squares = BitBoard::from_square(mv.src()) | BitBoard::from_square(mv.dest());
+                assert_eq!(squares & !board.mask(), BitBoard(0), "{mv} in\n{board}");
+            }
+        }
+    };
+
+    let mut rng = StdRng::seed_from_u64(0);
+    for size in SIZES {
+        random_games(size, 100, on_board);
+
+        // random positions, which don't need to be reachable
+        let mut positions = 0;
+        while positions < 1000 {
+            let white = rng.gen::<u64>() & rng.gen::<u64>() & BOARD_MASKS[size - 1];
+            let black = rng.gen::<u64>() & rng.gen::<u64>() & BOARD_MASKS[size - 1] & !white;
+            let side_to_move = if rng.gen() {
+                Color::White
+            } else {
+                Color::Black
+            };
+            if let Ok(board) =
+                Board::from_parts(size, BitBoard(white), BitBoard(black), side_to_move)
+            {
+                on_board(&board);
+                positions += 1;
+            }
+        }
+    }
+}
+
+#[test]
+fn big_boards() {
+    // both backends agree on boards up to 8x8
+    let mut rng = StdRng::seed_from_u64(0);
+    for size in SIZES {
+        for _ in 0..20 {
+            let mut board = Board::new(size);
+            let mut big = Board::<BitBoard128>::starting_position(size, size);
+            loop {
+                assert_eq!(big.to_notation(), board.to_notation());
+                assert_eq!(big.to_string(), board.to_string());
+                assert_eq!(big.outcome(), board.outcome());
+                assert_eq!(
+                    AdvancementHeuristic.value(&big, 0),
+                    AdvancementHeuristic.value(&board, 0)
+                );
+                let mut moves: Vec<String> =
+                    MoveGen::new(&board).map(|mv| mv.to_string()).collect();
+                let mut big_moves: Vec<String> =
+                    MoveGen::new(&big).map(|mv| mv.to_string()).collect();
+                moves.sort();
+                big_moves.sort();
+                assert_eq!(big_moves, moves);
+                if board.is_done() {
+                    break;
+                }
+
+                let mv = board.random_available_move(&mut rng);
+                board.play(mv);
+                big.play(big.parse_move(&mv.to_string()).unwrap());
+            }
+        }
+    }
+
+    let board = Board::<BitBoard128>::starting_position(11, 11);
+    assert_eq!(
+        board.to_notation(),
+        "ppppppppppp/11/11/11/11/11/11/11/11/11/PPPPPPPPPPP w"
+    );
+    assert_eq!(board.to_string().lines().nth(12), Some("abcdefghijk"));
+    assert_eq!(perft(&board, 1), 11);
+    assert_eq!(perft(&board, 2), 121);
+    assert_eq!(material_eval(board.pieces(Color::White)), 11);
+    assert_eq!(
+        advancement_eval(board.pieces(Color::Black), Color::Black, board.mask()),
+        11
+    );
+    assert_eq!(
+        board.parse_move("k1k2"),
+        Ok(Move::from_squares(
+            Square128::new(10, 0),
+            Square128::new(10, 1)
+        ))
+    );
+    assert_eq!(
+        "a10b11".parse::<Move<BitBoard128>>().unwrap().to_string(),
+        "a10b11"
+    );
+    assert_eq!(
+        "a12a11".parse::<Move<BitBoard128>>(),
+        Err(MoveError::Syntax("a12a11".to_string()))
+    );
+    assert_eq!(
+        "12/11/11/11/11/11/11/11/11/11/11 w".parse::<Board<BitBoard128>>(),
+        Err(ParseBoardError::InvalidSize(12))
+    );
+
+    // boards larger than 8x8 play out the same as the smaller ones
+    let mirror = D1Symmetry::new(true);
+    for (width, height) in [(9, 9), (10, 10), (11, 11), (11, 3), (3, 11), (9, 11)] {
+        let start = Board::<BitBoard128>::starting_position(width, height);
+        let mut history = GameHistory::new(start.clone());
+        while !history.board().is_done() {
+            let board = history.board();
+            assert_eq!(board.to_notation().parse().as_ref(), Ok(board));
+            assert_eq!(board.zobrist(), board.compute_zobrist());
+            assert_eq!(&board.map(mirror).map(mirror), board);
+            assert_eq!(board.occupied() & !board.mask(), BitBoard128(0));
+            for mv in MoveGen::new(board) {
+                assert_eq!(board.parse_move(&mv.to_string()), Ok(mv));
+            }
+
+            let mv = board.random_available_move(&mut rng);
+            history.play(mv);
+        }
+        assert_eq!(history.start(), start);
+    }
+}
+
+#[test]
+fn variant_rules() {
+    let double_step = Rules {
+        double_step: true,
+        ..Rules::STANDARD
+    };
+    let en_passant = Rules {
+        double_step: true,
+        en_passant: true,
+        ..Rules::STANDARD
+    };
+    let forced_capture = Rules {
+        forced_capture: true,
+        ..Rules::STANDARD
+    };
+    let sideways_capture = Rules {
+        sideways_capture: true,
+        ..Rules::STANDARD
+    };
+
+    // perft from depth 1 for each variant, with make and unmake
+    for reference in REFERENCE
+        .iter()
+        .filter(|r| r.rules != "standard" && r.size <= 5)
+    {
+        let rules: Rules = reference.rules.parse().unwrap();
+        let size = reference.size;
+        let mut board = reference.board();
+        for (depth, &nodes) in (1..=6).zip(reference.nodes) {
+            assert_eq!(
+                perft_make_unmake(&mut board, depth),
+                nodes,
+                "{rules} {size}x{size} depth {depth}"
+            );
+        }
+        assert_eq!(perft(&board, 3), reference.nodes[2]);
+        assert_eq!(board, Board::new(size).with_rules(rules));
+    }
+
+    // a double step past a pawn can be captured en passant, but only on the next move
+    let start: Board = "4p/5/2p2/5/P2P1 w".parse().unwrap();
+    let mut board = start.clone().with_rules(en_passant);
+    let undo = board.make(board.parse_move("d1d3").unwrap());
+    assert_eq!(board.en_passant(), Some(Square::D2));
+    assert_eq!(board.zobrist(), board.compute_zobrist());
+    let capture = board.parse_move("c3d2").unwrap();
+    let before = board.clone();
+    let capture_undo = board.make(capture);
+    assert_eq!(board.to_notation(), "4p/5/5/3p1/P4 w");
+    assert_eq!(board.zobrist(), board.compute_zobrist());
+    board.unmake(capture, capture_undo);
+    assert_eq!(board, before);
+    board.play(board.parse_move("e5e4").unwrap());
+    board.play(board.parse_move("a1a2").unwrap());
+    assert_eq!(
+        board.parse_move("c3d2"),
+        Err(MoveError::Illegal(Move::new(Square::C3, Square::D2)))
+    );
+    board = before;
+    board.unmake(Move::new(Square::D1, Square::D3), undo);
+    assert_eq!(board, start.clone().with_rules(en_passant));
+    // without en passant, the double step can't be captured
+    let mut board = start.with_rules(double_step);
+    board.play(board.parse_move("d1d3").unwrap());
+    assert_eq!(board.en_passant(), None);
+    assert!(board.parse_move("c3d2").is_err());
+
+    // with forced captures, the only moves are captures
+    let board = "ppp/1P1/P1P b"
+        .parse::<Board>()
+        .unwrap()
+        .with_rules(forced_capture);
+    let mut moves: Vec<Move> = board.available_moves().collect();
+    moves.sort();
+    assert_eq!(
+        moves,
+        [
+            Move::new(Square::A3, Square::B2),
+            Move::new(Square::C3, Square::B2)
+        ]
+    );
+    assert_eq!(MoveGen::with_mask(&board, Mask::Push).len(), 0);
+
+    // with sideways captures, pawns can also capture the pawn beside them
+    let start: Board = "4/4/pP2/4 w".parse().unwrap();
+    let mut board = start.clone().with_rules(sideways_capture);
+    let mut moves: Vec<Move> = board.available_moves().collect();
+    moves.sort();
+    assert_eq!(
+        moves,
+        [
+            Move::new(Square::B2, Square::A2),
+            Move::new(Square::B2, Square::B3)
+        ]
+    );
+    assert_eq!(MoveGen::with_mask(&board, Mask::Capture).len(), 1);
+    let capture = board.parse_move("b2a2").unwrap();
+    let undo = board.make(capture);
+    assert_eq!(board.to_notation(), "4/4/P3/4 b");
+    assert_eq!(board.zobrist(), board.compute_zobrist());
+    board.unmake(capture, undo);
+    assert_eq!(board, start.clone().with_rules(sideways_capture));
+    // and must take it if captures are forced
+    let board = start.clone().with_rules(Rules {
+        forced_capture: true,
+        ..sideways_capture
+    });
+    assert_eq!(board.available_moves().collect::<Vec<_>>(), [capture]);
+    // without sideways captures, the pawn can only push
+    assert_eq!(start.available_moves().count(), 1);
+    assert!(start.parse_move("b2a2").is_err());
+
+    // variant games can be recorded and replayed
+    assert_eq!("standard".parse(), Ok(Rules::STANDARD));
+    assert_eq!(en_passant.to_string(), "double-step en-passant");
+    assert_eq!(en_passant.to_string().parse(), Ok(en_passant));
+    assert_eq!(
+        "double-step sideways-capture".parse(),
+        Ok(Rules {
+            double_step: true,
+            ..sideways_capture
+        })
+    );
+    assert_eq!(
+        "double-step diagonal".parse::<Rules>(),
+        Err(RulesError::UnknownVariant("diagonal".to_string()))
+    );
+    let mut rng = StdRng::seed_from_u64(0);
+    for rules in [double_step, en_passant, forced_capture, sideways_capture] {
+        for size in SIZES {
+            let mut board = Board::new(size).with_rules(rules);
+            let mut record = GameRecord::new(board.clone(), "A", "B");
+            while !board.is_done() {
+                assert_eq!(board.zobrist(), board.compute_zobrist());
+                let mv = board.random_available_move(&mut rng);
+                record.moves.push(RecordedMove::new(mv));
+                board.play(mv);
+            }
+            record.outcome = board.outcome();
+            assert!(record.to_string().contains(&format!("[Rules \"{rules}\"]")));
+            assert_eq!(record.to_string().parse(), Ok(record));
+        }
+    }
+}
+
+#[test]
+fn misere() {
+    let misere = Rules {
+        misere: true,
+        ..Rules::STANDARD
+    };
+    let play = |moves: &[&str]| {
+        let mut board = Board::new(3).with_rules(misere);
+        for mv in moves {
+            board.play(board.parse_move(mv).unwrap());
+        }
+        board
+    };
+
+    // reaching the far rank loses
+    let board = play(&["a1a2", "c3c2", "a2b3"]);
+    assert_eq!(board.outcome(), Some(Outcome::WonBy(Player::B)));
+    let board = play(&["a1a2", "b3a2", "c1c2", "a2b1"]);
+    assert_eq!(board.outcome(), Some(Outcome::WonBy(Player::A)));
+    // having no moves wins, black is to move here
+    let board = play(&["a1a2", "b3b2", "c1c2"]);
+    assert_eq!(board.outcome(), Some(Outcome::WonBy(Player::B)));
+    // so the player who moves can lose, once a board is played in misère
+    assert!(misere.can_lose_after_move());
+    assert!(!Rules::STANDARD.can_lose_after_move());
+    assert!(Board::<BitBoard>::can_lose_after_move());
+
+    // the same games are played, only the outcomes differ
+    for depth in 1..=6 {
+        assert_eq!(
+            perft_make_unmake(&mut Board::new(3).with_rules(misere), depth),
+            perft_make_unmake(&mut Board::new(3), depth)
+        );
+    }
+
+    // the heuristics are inverted
+    let board = play(&["a1a2", "b3a2"]);
+    let standard = board.clone().with_rules(Rules::STANDARD);
+    assert_eq!(MaterialHeuristic.value(&standard, 0), -1);
+    assert_eq!(MaterialHeuristic.value(&board, 0), 1);
+    assert_eq!(
+        AdvancementHeuristic.value(&board, 0),
+        -AdvancementHeuristic.value(&standard, 0)
+    );
+
+    let mut rng = StdRng::seed_from_u64(0);
+    for size in SIZES {
+        let start = Board::new(size).with_rules(misere);
+        let record = play_game(
+            &start,
+            &mut MiniMaxBot::new(2, MaterialHeuristic, &mut rng),
+            &mut AlwaysPushBot::new(StdRng::seed_from_u64(size as u64)),
+        );
+        assert_eq!(record.outcome, record.board().outcome());
+        assert_eq!(record.to_string().parse(), Ok(record));
+    }
+}
+
+#[test]
+fn stalemate() {
+    let rules = |stalemate| Rules {
+        stalemate,
+        ..Rules::STANDARD
+    };
+    let blocked = |board: Board, stalemate| {
+        let board = board.with_rules(rules(stalemate));
+        assert_eq!(MoveGen::new(&board).len(), 0);
+        board.outcome()
+    };
+
+    // black is to move and blocked, with as many pawns as white
+    let mut even = Board::new(3);
+    for mv in ["a1a2", "b3b2", "c1c2"] {
+        even.play(even.parse_move(mv).unwrap());
+    }
+    // black is to move and blocked, with fewer pawns than white
+    let fewer = Board::from_parts(3, BitBoard(0x201), BitBoard(0x20000), Color::Black).unwrap();
+    // white is to move and blocked, with fewer pawns than black
+    let more = Board::from_parts(3, BitBoard(0x100), BitBoard(0x10400), Color::White).unwrap();
+
+    use StalemateRule::*;
+    assert_eq!(blocked(even.clone(), Draw), Some(Outcome::Draw));
+    assert_eq!(blocked(even.clone(), Loss), Some(Outcome::WonBy(Player::A)));
+    assert_eq!(blocked(even, Material), Some(Outcome::Draw));
+    assert_eq!(blocked(fewer.clone(), Draw), Some(Outcome::Draw));
+    assert_eq!(
+        blocked(fewer.clone(), Loss),
+        Some(Outcome::WonBy(Player::A))
+    );
+    assert_eq!(blocked(fewer, Material), Some(Outcome::WonBy(Player::A)));
+    assert_eq!(blocked(more.clone(), Draw), Some(Outcome::Draw));
+    assert_eq!(blocked(more.clone(), Loss), Some(Outcome::WonBy(Player::B)));
+    assert_eq!(
+        blocked(more.clone(), Material),
+        Some(Outcome::WonBy(Player::B))
+    );
+    // leaving the opponent without moves only loses with the material rule
+    assert!(!rules(Draw).can_lose_after_move());
+    assert!(!rules(Loss).can_lose_after_move());
+    assert!(rules(Material).can_lose_after_move());
+
+    // misère ignores the stalemate rule, having no moves wins
+    let misere = Rules {
+        misere: true,
+        ..rules(Loss)
+    };
+    assert_eq!(
+        more.with_rules(misere).outcome(),
+        Some(Outcome::WonBy(Player::A))
+    );
+
+    for stalemate in [Draw, Loss, Material] {
+        // the rule is kept in text
+        assert_eq!(rules(stalemate).to_string().parse(), Ok(rules(stalemate)));
+
+        // the same games are played, only the outcomes differ
+        for depth in 1..=6 {
+            assert_eq!(
+                perft_make_unmake(&mut Board::new(3).with_rules(rules(stalemate)), depth),
+                perft_make_unmake(&mut Board::new(3), depth)
+            );
+        }
+
+        // games are reproducible under every rule
+        for size in SIZES {
+            let start = Board::new(size).with_rules(rules(stalemate));
+            let game = |seed| {
+                play_game(
+                    &start,
+                    &mut MiniMaxBot::new(2, MaterialHeuristic, StdRng::seed_from_u64(seed)),
+                    &mut RandomBot::new(StdRng::seed_from_u64(seed + 1)),
+                )
+            };
+            let record = game(size as u64);
+            let replay = game(size as u64);
+            let moves = |record: &GameRecord| record.moves.iter().map(|m| m.mv).collect::<Vec<_>>();
+            assert_eq!(moves(&record), moves(&replay));
+            assert_eq!(record.outcome, replay.outcome);
+            assert_eq!(record.outcome, record.board().outcome());
+            assert_eq!(record.to_string().parse(), Ok(record));
+        }
+    }
+}
+
+#[test]
+fn sized_board() {
+    use super::sized_board::{SizeError, SizedBoard};
+    use crate::with_size;
+
+    for size in SIZES {
+        with_size!(size, N => {
+            let board = SizedBoard::<N>::new();
+            assert_eq!(Board::from(board.clone()), Board::new(size));
+            assert_eq!(SizedBoard::<N>::MASK, Board::new(size).mask());
+            assert_eq!(perft(&board, 3), perft(&Board::new(size), 3));
+            assert_eq!(board.map(D1Symmetry::new(true)), board);
+        });
+
+        // every position has the same outcome
+        random_games(size, 20, |board| {
+            with_size!(size, N => {
+                let sized = SizedBoard::<N>::try_from(board.clone()).unwrap();
+                assert_eq!(sized.outcome(), board.outcome());
+                assert_eq!(sized.board(), board);
+                // and the same moves, generated with the constant playing area
+                if !board.is_done() {
+                    let moves: Vec<Move> = sized.available_moves().collect();
+                    assert_eq!(moves, MoveGen::new(board).collect::<Vec<_>>());
+                }
+            })
+        });
+    }
+
+    assert_eq!(
+        SizedBoard::<4>::try_from(Board::new(3)),
+        Err(SizeError::WrongSize {
+            expected: 4,
+            width: 3,
+            height: 3
+        })
+    );
+    assert!(SizedBoard::<3>::try_from(Board::with_dimensions(3, 4)).is_err());
+
+    // the rules carry over
+    let misere = Rules {
+        misere: true,
+        ..Rules::STANDARD
+    };
+    let board = SizedBoard::<3>::new().with_rules(misere);
+    assert!(board.misere());
+    assert_eq!(board.board().rules(), misere);
+
+    // bots run on a sized board
+    let result = run(
+        SizedBoard::<4>::new,
+        || MiniMaxBot::new(3, MaterialHeuristic, StdRng::seed_from_u64(0)),
+        || AlwaysPushBot::new(StdRng::seed_from_u64(1)),
+        5,
+        true,
+        |_, _| {},
+    );
+    for replay in &result.replays {
+        let record = GameRecord::from(replay);
+        assert_eq!(record.board().outcome(), Some(replay.outcome));
+    }
+}
+
+#[test]
+fn move_gen_without_allocation() {
+    let check = |gen: MoveGen| {
+        let len = gen.len();
+        assert_eq!(gen.has_any_move(), len > 0);
+        assert_eq!(gen.count(), len);
+    };
+
+    for size in SIZES {
+        random_games(size, 50, |board| {
+            check(MoveGen::new(board));
+            check(MoveGen::with_mask(board, Mask::Capture));
+            check(MoveGen::with_mask(board, Mask::Push));
+            assert_eq!(board.has_any_move(), MoveGen::new(board).len() > 0);
+
+            // len counts the remaining moves while iterating
+            let mut gen = MoveGen::new(board);
+            for left in (0..gen.len()).rev() {
+                gen.next().unwrap();
+                assert_eq!(gen.len(), left);
+                assert_eq!(gen.has_any_move(), left > 0);
+            }
+        });
+    }
+}
+
+#[test]
+fn pawn_targets() {
+    fn check<BB: BitBoardBackend>(board: &Board<BB>) {
+        let color = board.side_to_move();
+        let generate = |pawns| {
+            PawnTargets::generate(
+                pawns,
+                color,
+                board.width(),
+                board.empty() & board.mask(),
+                board.pieces_not_to_move(),
+                Some(0),
+            )
+            .with_sideways(pawns, board.width(), board.pieces_not_to_move())
+        };
+        let targets = generate(board.pieces_to_move());
+
+        // shifting the whole set is the same as shifting each pawn
+        let mut union = PawnTargets {
+            straight: BB::EMPTY,
+            double: BB::EMPTY,
+            left: BB::EMPTY,
+            right: BB::EMPTY,
+            sideways_left: BB::EMPTY,
+            sideways_right: BB::EMPTY,
+        };
+        for src in board.pieces_to_move().indices() {
+            let single = generate(BB::from_index(src));
+            union.straight |= single.straight;
+            union.double |= single.double;
+            union.left |= single.left;
+            union.right |= single.right;
+            union.sideways_left |= single.sideways_left;
+            union.sideways_right |= single.sideways_right;
+        }
+        assert_eq!(targets, union);
+        assert!((targets.all() & !board.mask()).is_empty());
+
+        // every move goes one file over at most, without wrapping around
+        for mv in MoveGen::from_targets(color, targets) {
+            let (src, dest) = (mv.src_index(), mv.dest_index());
+            assert!(!(board.pieces_to_move() & BB::from_index(src)).is_empty());
+            assert!((src % BB::STRIDE).abs_diff(dest % BB::STRIDE) <= 1);
+        }
+    }
+
+    for size in SIZES {
+        random_games(size, 20, check);
+    }
+    // on the bigger bitboard, up to the full 11x11 board without a padding file
+    let mut rng = StdRng::seed_from_u64(0);
+    for size in [8, 11] {
+        let mut board = Board::<BitBoard128>::starting_position(size, size);
+        while !board.is_done() {
+            check(&board);
+            board.play(board.random_available_move(&mut rng));
+        }
+    }
+}
+
+#[test]
+fn staged_move_gen() {
+    use super::move_order::*;
+
+    fn check<B: PawnBoard>(board: &B, order: Option<&dyn MoveOrder<B::BB>>) {
+        let mut staged = match order {
+            Some(order) => StagedMoveGen::with_order(board, order),
+            None => StagedMoveGen::new(board),
+        };
+        let far = board.win_rank(board.side_to_move());
+
+        let mut moves = vec![];
+        let mut last: Option<(Stage, i32)> = None;
+        while let Some(mv) = staged.next() {
+            let dest = <B::BB>::from_index(mv.dest_index());
+            let stage = staged.stage();
+            match stage {
+                Stage::Wins | Stage::Losses => assert!(!(dest & far).is_empty()),
+                Stage::Captures => assert!(!(dest & board.capture_squares()).is_empty()),
+                Stage::Pushes => assert!((dest & (far | board.capture_squares())).is_empty()),
+            }
+            assert_eq!(
+                stage == Stage::Losses,
+                board.misere() && !(dest & far).is_empty()
+            );
+
+            // stages in order, and scores in order within a stage
+            let score = order.map_or(0, |order| order.score(mv));
+            if let Some((last_stage, last_score)) = last {
+                assert!(last_stage <= stage);
+                assert!(last_stage < stage || last_score >= score);
+            }
+            last = Some((stage, score));
+            moves.push(mv);
+        }
+
+        let mut all = board.masked_moves(Mask::None);
+        all.sort();
+        moves.sort();
+        assert_eq!(moves, all);
+    }
+
+    let mut history = History::new();
+    let mut killers = Killers::new();
+    let mut rng = StdRng::seed_from_u64(0);
+    for size in SIZES {
+        random_games(size, 20, |board| {
+            for mv in board.masked_moves(Mask::None) {
+                if rng.gen_bool(0.2) {
+                    history.add(mv, rng.gen_range(1..5));
+                }
+            }
+            check(board, None);
+            check(board, Some(&history));
+        });
+    }
+
+    // killers come first within their stage
+    let board = Board::new(4);
+    let killer = board.parse_move("d1d2").unwrap();
+    killers.store(0, board.parse_move("c1c2").unwrap());
+    killers.store(0, killer);
+    killers.store(0, killer);
+    assert_eq!(killers.score(0, killer), Killers::<BitBoard>::FIRST);
+    assert_eq!(
+        killers.score(0, board.parse_move("c1c2").unwrap()),
+        Killers::<BitBoard>::SECOND
+    );
+    assert_eq!(killers.score(1, killer), 0);
+    let order = |mv| killers.score(0, mv) + history.score(mv);
+    let mut staged = StagedMoveGen::with_order(&board, &order);
+    assert_eq!(staged.next(), Some(killer));
+    assert_eq!(staged.next().map(|mv| mv.to_string()), Some("c1c2".into()));
+    check(&board, Some(&order));
+
+    // wins come first, and last in misère
+    let mut board = Board::new(3);
+    for mv in ["a1a2", "c3c2"] {
+        board.play(board.parse_move(mv).unwrap());
+    }
+    let win = board.parse_move("a2b3").unwrap();
+    assert_eq!(StagedMoveGen::new(&board).next(), Some(win));
+    let misere = board.with_rules(Rules {
+        misere: true,
+        ..Rules::STANDARD
+    });
+    assert_eq!(StagedMoveGen::new(&misere).last(), Some(win));
+    check(&misere, None);
+
+    // breakthrough
+    let mut board = super::breakthrough::Board::new(5);
+    while !board.is_done() {
+        check(&board, None);
+        board.play(board.random_available_move(&mut rng));
+    }
+}
+
+#[test]
+fn perft_reference() {
+    // every size in SIZES has standard reference values
+    for size in SIZES {
+        assert!(REFERENCE
+            .iter()
+            .any(|r| r.size == size && r.rules == "standard"));
+    }
+
+    for reference in REFERENCE {
+        let board = reference.board();
+        let ref_board = RefBoard::from_board(&board);
+        for (depth, &nodes) in (1..).zip(reference.nodes) {
+            let name = format!(
+                "{} {}x{} depth {depth}",
+                reference.rules, reference.size, reference.size
+            );
+            assert_eq!(perft(&board, depth), nodes, "{name}");
+            // and the reference implementation, so the values don't only come from MoveGen
+            assert_eq!(ref_board.perft(depth), nodes, "reference {name}");
+        }
+
+        // the divide adds up to the perft of the next depth
+        let divide = divide(&board, 2);
+        assert_eq!(divide.len() as u64, reference.nodes[0]);
+        assert_eq!(
+            divide.iter().map(|(_, nodes)| nodes).sum::<u64>(),
+            reference.nodes[1]
+        );
+    }
+
+    // misère and the stalemate rules play the same games
+    for rules in ["misere", "stalemate-loss", "stalemate-material"] {
+        let board = Board::new(4).with_rules(rules.parse().unwrap());
+        assert_eq!(perft(&board, 6), 4548);
+    }
+}
+
+/// Plays seeded random games from `start`, checking the moves, outcome and
+/// every played move against the [reference](super::reference) implementation
+fn differential<BB: BitBoardBackend>(start: Board<BB>, games: u64, seed: u64) {
+    let coord = |index: usize| (index % BB::STRIDE, index / BB::STRIDE);
+    let to_ref = |mv: Move<BB>| -> RefMove { (coord(mv.src_index()), coord(mv.dest_index())) };
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    for _ in 0..games {
+        let mut board = start.clone();
+        let mut reference = RefBoard::from_board(&board);
+        loop {
+            let mut moves: Vec<RefMove> = MoveGen::new(&board).map(to_ref).collect();
+            let mut expected = reference.moves();
+            moves.sort();
+            expected.sort();
+            assert_eq!(moves, expected, "moves in\n{board}");
+            assert_eq!(board.outcome(), reference.outcome(), "outcome in\n{board}");
+
+            if board.is_done() {
+                break;
+            }
+            let mv = board.random_available_move(&mut rng);
+            board.play(mv);
+            reference.play(to_ref(mv));
+            assert_eq!(
+                RefBoard::from_board(&board),
+                reference,
+                "after {mv} in\n{board}"
+            );
+        }
+    }
+}
+
+#[test]
+fn differential_testing() {
+    let rule_sets = [
+        "standard",
+        "double-step",
+        "double-step en-passant",
+        "forced-capture",
+        "double-step en-passant forced-capture",
+        "misere stalemate-loss",
+        "stalemate-material",
+        "sideways-capture",
+        "double-step en-passant forced-capture sideways-capture",
+    ];
+    for (seed, rules) in rule_sets.into_iter().enumerate() {
+        let rules: Rules = rules.parse().unwrap();
+        for size in SIZES {
+            let board = Board::new(size).with_rules(rules);
+            differential(board, 300, seed as u64 * 100 + size as u64);
+        }
+        // rectangular boards and the bigger bitboard
+        differential(
+            Board::with_dimensions(5, 3).with_rules(rules),
+            100,
+            seed as u64,
+        );
+        differential(
+            Board::with_dimensions(3, 7).with_rules(rules),
+            100,
+            seed as u64,
+        );
+        for size in 9..=11 {
+            let board = Board::<BitBoard128>::starting_position(size, size).with_rules(rules);
+            differential(board, 20, seed as u64 * 100 + size as u64);
+        }
+    }
+}
+
+/// Returns the score of a position for the side to move by plain negamax, without pruning:
+/// `1000 - ply` for wins at `ply` plies from the root, 0 for draws
+fn brute_force(board: &Board, ply: i32) -> i32 {
+    if let Some(outcome) = board.outcome() {
+        return match outcome.pov(board.next_player()) {
+            OutcomeWDL::Win => 1000 - ply,
+            OutcomeWDL::Draw => 0,
+            OutcomeWDL::Loss => -(1000 - ply),
+        };
+    }
+    MoveGen::new(board)
+        .map(|mv| -brute_force(&board.clone_and_play(mv), ply + 1))
+        .max()
+        .unwrap()
+}
+
+#[test]
+fn solver() {
+    // 3x3 hexapawn with the original rules, where a blocked side loses, is a win for black
+    let board = Board::new(3).with_rules("stalemate-loss".parse().unwrap());
+    let solution = Solver::new().solve(&board).unwrap();
+    assert_eq!(solution.outcome, OutcomeWDL::Loss);
+    assert_eq!(solution.to_string(), "loss in 6 plies");
+
+    let rule_sets = [
+        "standard",
+        "double-step en-passant",
+        "forced-capture",
+        "misere",
+        "stalemate-loss",
+        "stalemate-material",
+        "sideways-capture",
+    ];
+    for rules in rule_sets {
+        let rules: Rules = rules.parse().unwrap();
+        let mut starts = vec![
+            Board::new(3).with_rules(rules),
+            Board::with_dimensions(4, 3).with_rules(rules),
+            Board::with_dimensions(3, 4).with_rules(rules),
+        ];
+        // the 4x4 game tree is too big to brute force with double steps or sideways captures
+        if !rules.double_step && !rules.sideways_capture {
+            starts.push(Board::new(4).with_rules(rules));
+        }
+
+        for start in starts {
+            let mut solver = Solver::new();
+            let solution = solver.solve(&start).unwrap();
+
+            // the same value as searching every move
+            let score = brute_force(&start, 0);
+            let expected = match score {
+                0 => OutcomeWDL::Draw,
+                s if s > 0 => OutcomeWDL::Win,
+                _ => OutcomeWDL::Loss,
+            };
+            assert_eq!(solution.outcome, expected, "{rules} in\n{start}");
+            if expected != OutcomeWDL::Draw {
+                assert_eq!(solution.distance as i32, 1000 - score.abs(), "{rules}");
+            }
+
+            // playing the principal variation reaches the solved outcome
+            let mut board = start.clone();
+            for &mv in &solution.principal_variation {
+                assert!(board.is_available_move(mv));
+                board.play(mv);
+            }
+            assert_eq!(
+                solution.distance as usize,
+                solution.principal_variation.len()
+            );
+            assert_eq!(
+                board.outcome().unwrap().pov(start.next_player()),
+                solution.outcome
+            );
+            assert_eq!(
+                solution.best_move(),
+                solution.principal_variation.first().copied()
+            );
+
+            // mirrored positions have the same value
+            let after = start.clone_and_play(solution.best_move().unwrap());
+            let mirrored = after.map(D1Symmetry::new(true));
+            assert_eq!(
+                solver.solve(&after).unwrap().outcome,
+                solver.solve(&mirrored).unwrap().outcome
+            );
+        }
+    }
+
+    // giving up at the node limit
+    assert!(Solver::with_node_limit(100).solve(&Board::new(5)).is_none());
+}
+
+#[test]
+fn tablebase() {
+    let mut rng = StdRng::seed_from_u64(0);
+    for rules in [
+        "standard",
+        "double-step en-passant",
+        "misere",
+        "stalemate-material",
+    ] {
+        let rules: Rules = rules.parse().unwrap();
+        for start in [
+            Board::new(3).with_rules(rules),
+            Board::new(4).with_rules(rules),
+            Board::with_dimensions(5, 3).with_rules(rules),
+        ] {
+            let tablebase = Tablebase::generate_reachable(&start).unwrap();
+
+            // the same values as the solver, on random games
+            let mut solver = Solver::new();
+            for _ in 0..20 {
+                let mut board = start.clone();
+                loop {
+                    let value = tablebase.probe(&board).unwrap();
+                    let solution = solver.solve(&board).unwrap();
+                    assert_eq!(value.outcome, solution.outcome, "{rules} in\n{board}");
+                    if value.outcome != OutcomeWDL::Draw {
+                        assert_eq!(value.distance, solution.distance, "{rules} in\n{board}");
+                    }
+                    assert_eq!(
+                        tablebase.probe(&board.map(D1Symmetry::new(true))),
+                        Some(value)
+                    );
+
+                    if board.is_done() {
+                        assert_eq!(value.distance, 0);
+                        break;
+                    }
+                    board.play(board.random_available_move(&mut rng));
+                }
+            }
+
+            // reading what was written gives the same tablebase
+            let mut file = vec![];
+            tablebase.write(&mut file).unwrap();
+            assert_eq!(Tablebase::read(&file[..]).unwrap(), tablebase);
+        }
+    }
+
+    let tablebase = Tablebase::generate_reachable(&Board::new(3)).unwrap();
+    assert_eq!(tablebase.len(), 71);
+    assert_eq!(tablebase.probe(&Board::new(3)).unwrap().to_string(), "draw");
+
+    // positions of other sizes or rules aren't in the tablebase
+    assert_eq!(tablebase.probe(&Board::new(4)), None);
+    let misere = Board::new(3).with_rules("misere".parse().unwrap());
+    assert_eq!(tablebase.probe(&misere), None);
+    // neither are unreachable positions
+    let unreachable: Board = "3/PPP/3 b".parse().unwrap();
+    assert_eq!(tablebase.probe(&unreachable), None);
+
+    // unless every legal position is enumerated
+    let all = Tablebase::generate_all::<BitBoard>(3, 3, Rules::STANDARD).unwrap();
+    assert!(all.len() > tablebase.len());
+    let mut solver = Solver::new();
+    for board in ["3/PPP/3 b", "p2/1P1/3 w", "1p1/P2/2P b", "pp1/2P/3 w"] {
+        let board: Board = board.parse().unwrap();
+        let value = all.probe(&board).unwrap();
+        let solution = solver.solve(&board).unwrap();
+        assert_eq!(value.outcome, solution.outcome, "{board}");
+        if value.outcome != OutcomeWDL::Draw {
+            assert_eq!(value.distance, solution.distance, "{board}");
+        }
+    }
+    random_games(3, 20, |board| {
+        assert_eq!(all.probe(board), tablebase.probe(board));
+    });
+    assert!(Tablebase::generate_all::<BitBoard>(4, 3, Rules::STANDARD).is_ok());
+    for size in [4, 5] {
+        assert!(matches!(
+            Tablebase::generate_all::<BitBoard>(size, size, Rules::STANDARD),
+            Err(TablebaseError::TooBig { .. })
+        ));
+    }
+
+    // invalid files
+    let mut file = vec![];
+    tablebase.write(&mut file).unwrap();
+    assert!(matches!(
+        Tablebase::read(&b"not a tablebase"[..]),
+        Err(TablebaseError::InvalidFormat(_))
+    ));
+    assert!(matches!(
+        Tablebase::read(&file[..file.len() - 1]),
+        Err(TablebaseError::Io(_))
+    ));
+    // a corrupt size or amount of positions fails without allocating for it
+    let len_offset = 8 + file[7] as usize;
+    let corrupt = |offset: usize, bytes: &[u8]| {
+        let mut file = file.clone();
+        file[offset..offset + bytes.len()].copy_from_slice(bytes);
+        Tablebase::read(&file[..])
+    };
+    assert!(matches!(
+        corrupt(5, &[12]),
+        Err(TablebaseError::InvalidFormat(_))
+    ));
+    assert!(matches!(
+        corrupt(len_offset, &u64::MAX.to_le_bytes()),
+        Err(TablebaseError::InvalidFormat(_))
+    ));
+    assert!(matches!(
+        corrupt(len_offset, &157_464u64.to_le_bytes()),
+        Err(TablebaseError::Io(_)) | Err(TablebaseError::InvalidFormat(_))
+    ));
+    // so do keys that are too long, overflow or are outside of the keys of the size
+    let keys_offset = len_offset + 8;
+    let invalid_key = |bytes: &[u8], msg: &str| match corrupt(keys_offset, bytes) {
+        Err(TablebaseError::InvalidFormat(err)) => assert_eq!(err, msg),
+        result => panic!("expected '{msg}', got {result:?}"),
+    };
+    invalid_key(
+        &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x81],
+        "key too long",
+    );
+    invalid_key(
+        &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02],
+        "key out of range",
+    );
+    // 3^9 * 2 * 4 = 157464 is the first key past the 3x3 ones
+    invalid_key(&[0x98, 0xce, 0x09], "key out of range");
+    assert!(matches!(
+        Tablebase::generate_reachable(&Board::<BitBoard128>::starting_position(8, 8)),
+        Err(TablebaseError::TooBig { .. })
+    ));
+}
+
+#[test]
+fn tablebase_bot() {
+    use super::sized_board::SizedBoard;
+
+    let rules: Rules = "stalemate-loss".parse().unwrap();
+    for size in [3, 4] {
+        let start = Board::new(size).with_rules(rules);
+        let tablebase = Tablebase::generate_reachable(&start).unwrap();
+        let value = tablebase.probe(&start).unwrap();
+        let bot = |seed| {
+            TablebaseBot::new(
+                Some(&tablebase),
+                RandomBot::new(StdRng::seed_from_u64(seed)),
+                StdRng::seed_from_u64(seed),
+            )
+        };
+
+        // perfect play against itself lasts exactly as long as the tablebase says
+        let record = play_game(&start, &mut bot(0), &mut bot(1));
+        assert_eq!(record.moves.len() as u32, value.distance);
+        assert_eq!(
+            record.board().outcome().unwrap().pov(start.next_player()),
+            value.outcome
+        );
+
+        // the side that wins with perfect play always wins against random moves
+        for seed in 0..20 {
+            let mut random = RandomBot::new(StdRng::seed_from_u64(seed));
+            let (record, winner) = match value.outcome {
+                OutcomeWDL::Win => (play_game(&start, &mut bot(seed), &mut random), Player::A),
+                _ => (play_game(&start, &mut random, &mut bot(seed)), Player::B),
+            };
+            assert_eq!(record.board().outcome(), Some(Outcome::WonBy(winner)));
+        }
+    }
+
+    // the best moves are the ones keeping the value
+    let start = Board::new(4).with_rules(rules);
+    let tablebase = Tablebase::generate_reachable(&start).unwrap();
+    random_games(4, 20, |board| {
+        let board = board.clone().with_rules(rules);
+        let Some(moves) = tablebase.best_moves(&board) else {
+            assert!(board.is_done());
+            return;
+        };
+        let value = tablebase.probe(&board).unwrap();
+        for mv in moves {
+            let child = tablebase.probe(&board.clone_and_play(mv)).unwrap();
+            assert_eq!(child.outcome, value.outcome.flip());
+            if value.outcome != OutcomeWDL::Draw {
+                assert_eq!(child.distance + 1, value.distance);
+            }
+        }
+    });
+
+    // positions the tablebase doesn't cover go to the fallback, on any board that converts to a `Board`
+    let mut bot = TablebaseBot::new(
+        Some(&tablebase),
+        MiniMaxBot::new(2, MaterialHeuristic, StdRng::seed_from_u64(0)),
+        StdRng::seed_from_u64(0),
+    );
+    let board = SizedBoard::<5>::new();
+    assert!(board.is_available_move(bot.select_move(&board)));
+    assert_eq!(
+        format!("{bot:?}"),
+        "TablebaseBot { fallback: MiniMaxBot { depth: 2, heuristic: MaterialHeuristic } }"
+    );
+}
+
+#[test]
+fn alpha_beta_bot() {
+    use super::sized_board::SizedBoard;
+
+    // searching to the end plays the same as the tablebase
+    let rules: Rules = "stalemate-loss".parse().unwrap();
+    let tablebase = Tablebase::generate_reachable(&Board::new(4).with_rules(rules)).unwrap();
+    let mut bot = AlphaBetaBot::new(40, SolverHeuristicSimplified);
+    random_games(4, 10, |board| {
+        let board = board.clone().with_rules(rules);
+        if board.is_done() {
+            return;
+        }
+        let value = tablebase.probe(&board).unwrap();
+        let mv = bot.select_move(&board);
+        let child = tablebase.probe(&board.clone_and_play(mv)).unwrap();
+        assert_eq!(child.outcome.flip(), value.outcome, "{mv} in\n{board}");
+        if value.outcome != OutcomeWDL::Draw {
+            assert_eq!(child.distance + 1, value.distance, "{mv} in\n{board}");
+        }
+    });
+
+    // the principal variation is legal and starts with the move played
+    let check_pv = |bot: &AlphaBetaBot<MaterialHeuristic>, board: &Board, mv| {
+        assert_eq!(bot.principal_variation()[0], mv);
+        let mut board = board.clone();
+        for &mv in bot.principal_variation() {
+            assert!(board.is_available_move(mv));
+            board.play(mv);
+        }
+    };
+
+    // limited by depth
+    let board = Board::new(8);
+    let mut bot = AlphaBetaBot::new(4, MaterialHeuristic);
+    let mv = bot.select_move(&board);
+    assert_eq!(bot.depth(), 4);
+    assert!(bot.principal_variation().len() <= 4);
+    check_pv(&bot, &board, mv);
+
+    // limited by nodes, playing the move of the last completed iteration
+    let mut bot = AlphaBetaBot::new(40, MaterialHeuristic).with_node_limit(5000);
+    let mv = bot.select_move(&board);
+    assert!(bot.nodes() <= 5001);
+    assert!((1..40).contains(&bot.depth()));
+    check_pv(&bot, &board, mv);
+    assert_eq!(
+        format!("{bot:?}"),
+        "AlphaBetaBot { depth: 40, node_limit: Some(5000), heuristic: MaterialHeuristic }"
+    );
+
+    // the transposition table is kept between moves
+    let mut bot = AlphaBetaBot::new(6, MaterialHeuristic);
+    let board = Board::new(5);
+    bot.select_move(&board);
+    let first = bot.nodes();
+    bot.select_move(&board);
+    assert!(bot.nodes() < first);
+
+    // on any board that converts to a `Board`
+    let board = SizedBoard::<6>::new();
+    assert!(board.is_available_move(bot.select_move(&board)));
+
+    // the key doesn't cover the rules, so moves from the table are checked before they're played
+    let forced_capture: Rules = "forced-capture".parse().unwrap();
+    let mut bot = AlphaBetaBot::new(4, MaterialHeuristic);
+    random_games(4, 20, |board| {
+        if board.is_done() {
+            return;
+        }
+        bot.select_move(board);
+        let board = board.clone().with_rules(forced_capture);
+        assert!(board.is_available_move(bot.select_move(&board)));
+    });
+}
+
+#[test]
+fn clock() {
+    let ms = Duration::from_millis;
+    let mut rng = StdRng::seed_from_u64(0);
+
+    // the same budget for every move
+    let mut clock = Clock::new(TimeControl::PerMove(ms(50)));
+    assert_eq!(clock.budget(), ms(50));
+    clock.record(ms(80));
+    assert_eq!(clock.budget(), ms(50));
+
+    // a share of the time left plus the increment
+    let time_control = TimeControl::PerGame {
+        time: ms(1600),
+        increment: ms(10),
+    };
+    let mut clock = Clock::new(time_control);
+    assert_eq!(clock.remaining(), ms(1600));
+    assert_eq!(clock.budget(), ms(110));
+    clock.record(ms(210));
+    assert_eq!(clock.remaining(), ms(1400));
+    assert_eq!(clock.budget(), ms(97) + Duration::from_micros(500));
+
+    // but never more than the time left
+    clock.record(ms(2000));
+    assert_eq!(clock.remaining(), ms(10));
+    assert_eq!(clock.budget(), ms(10));
+    assert_eq!(time_control.to_string(), "1.6s per game + 10ms per move");
+
+    // the searches stop when the time runs out, even without other limits
+    let board = Board::new(8);
+    let start = Instant::now();
+    let mut bot = MCTSHeuristicBot::new(u64::MAX, 2.0, MaterialHeuristic, rng.clone())
+        .with_time_control(TimeControl::PerMove(ms(20)));
+    assert!(board.is_available_move(bot.select_move(&board)));
+    assert!(start.elapsed() < ms(1000));
+    assert_eq!(
+        format!("{bot:?}"),
+        "MCTSHeuristicBot { iterations: unlimited, exploration_weight: 2, heuristic: MaterialHeuristic }"
+    );
+
+    let start = Instant::now();
+    let mut bot = AlphaBetaBot::new(u32::MAX, MaterialHeuristic)
+        .with_time_control(TimeControl::PerMove(ms(20)));
+    assert!(board.is_available_move(bot.select_move(&board)));
+    assert!(start.elapsed() < ms(1000));
+    assert!(bot.depth() >= 1);
+
+    // a whole game keeps to the time for the game and its increments
+    let time_control = TimeControl::PerGame {
+        time: ms(100),
+        increment: ms(5),
+    };
+    let mut bot = AlphaBetaBot::new(u32::MAX, MaterialHeuristic).with_time_control(time_control);
+    let mut board = Board::new(6);
+    let mut moves = 0;
+    let start = Instant::now();
+    while !board.is_done() {
+        let mv = if board.next_player() == Player::A {
+            moves += 1;
+            bot.select_move(&board)
+        } else {
+            board.random_available_move(&mut rng)
+        };
+        board.play(mv);
+    }
+    let limit = ms(100) + ms(5) * moves + ms(20) * moves;
+    assert!(start.elapsed() < limit);
+    assert!(bot.clock().unwrap().remaining() > Duration::ZERO);
+    assert_ne!(bot.clock().unwrap().remaining(), ms(100));
+}
+
+#[test]
+fn breakthrough() {
+    use super::breakthrough::Board as Breakthrough;
+
+    let board = Breakthrough::new(8);
+    assert_eq!(perft(&board, 1), 22);
+    assert_eq!(perft(&board, 2), 484);
+    assert_eq!(perft(&board, 3), 11132);
+    assert_eq!(board.pieces(Color::White), BitBoard(0xFFFF));
+    assert_eq!(board.pieces(Color::Black), BitBoard(0xFFFF << 48));
+
+    // straight moves can't capture, diagonal moves can
+    let mut board = Breakthrough::with_dimensions(3, 4);
+    assert_eq!(
+        board.parse_move("c2c3"),
+        Err(MoveError::Illegal(Move::new(Square::C2, Square::C3)))
+    );
+    let mut captures: Vec<Move> = board.masked_moves(Mask::Capture);
+    captures.sort();
+    assert_eq!(
+        captures,
+        [
+            Move::new(Square::A2, Square::B3),
+            Move::new(Square::B2, Square::A3),
+            Move::new(Square::B2, Square::C3),
+            Move::new(Square::C2, Square::B3)
+        ]
+    );
+    for mv in ["a2b3", "a3b2"] {
+        board.play(board.parse_move(mv).unwrap());
+    }
+    assert_eq!(board.outcome(), None);
+    board.play(board.parse_move("b3a4").unwrap());
+    assert_eq!(board.outcome(), Some(Outcome::WonBy(Player::A)));
+    let mirror = D1Symmetry::new(true);
+    let mut rng = StdRng::seed_from_u64(0);
+    for (width, height) in [(3, 4), (5, 5), (8, 8), (6, 4)] {
+        let mut board = Breakthrough::with_dimensions(width, height);
+        while !board.is_done() {
+            assert_eq!(board.map(mirror).map(mirror), board);
+            assert_eq!(board.occupied() & !board.mask(), BitBoard(0));
+            let mut moves: Vec<Move> = board
+                .available_moves()
+                .map(|mv| board.map_move(mirror, mv))
+                .collect();
+            let mut mirrored_moves: Vec<Move> = board.map(mirror).available_moves().collect();
+            moves.sort();
+            mirrored_moves.sort();
+            assert_eq!(moves, mirrored_moves);
+            board.play(board.random_available_move(&mut rng));
+        }
+    }
+
+    // every bot and the tournament runner work on breakthrough
+    macro_rules! run_bot {
+        ($bot:expr) => {
+            let result = run(
+                || Breakthrough::new(5),
+                || $bot,
+                || RandomBot::new(StdRng::seed_from_u64(1)),
+                2,
+                true,
+                |_, _| {},
+            );
+            assert_eq!(result.replays.len(), 4);
+        };
+    }
+    let rng = || StdRng::seed_from_u64(0);
+    run_bot!(RandomBot::new(rng()));
+    run_bot!(AlwaysPushBot::new(rng()));
+    run_bot!(AlwaysCaptureBot::new(rng()));
+    run_bot!(MiniMaxBot::new(2, SolverHeuristicSimplified, rng()));
+    run_bot!(MiniMaxBot::new(2, MaterialHeuristic, rng()));
+    run_bot!(MiniMaxBot::new(2, AdvancementHeuristic, rng()));
+    run_bot!(MCTSBot::new(50, 2.0, rng()));
+    run_bot!(MCTSHeuristicBot::new(50, 2.0, MaterialHeuristic, rng()));
+    run_bot!(MCTSHeuristicBot::new(50, 2.0, AdvancementHeuristic, rng()));
+}